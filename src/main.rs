@@ -1,304 +1,250 @@
-use rand::prelude::*;
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
 use theory::*;
 
-fn sign(a: i8) -> i8 {
-    if a >= 0 {
-        1
-    } else {
-        -1
-    }
+const USAGE: &str = "Usage: counterpoint [--input <file>|-] [--scale <root> <type>] [--direction above|below] [--budget <steps>] [--format text|midi|wav|lilypond|abc|musicxml] [--output <file>]";
+
+/// How the result is written out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    /// The cantus and the counterpoint each on a line of space-separated pitches
+    Text,
+    Midi,
+    /// A sine-tone preview of both voices
+    Wav,
+    LilyPond,
+    Abc,
+    MusicXml,
 }
 
-fn shuffle<T>(val: &mut [T]) {
-    let mut rng = rand::thread_rng();
-    for i in (1..val.len()).into_iter().rev() {
-        let idx = rng.gen_range(0, i);
-        val.swap(i, idx)
-    }
+struct Options {
+    /// The file to read the cantus firmus from, or `None` to read it from stdin
+    input: Option<String>,
+    scale: Scale,
+    direction: Direction,
+    budget: usize,
+    format: Format,
+    /// The file to write the result to, or `None` to write it to stdout
+    output: Option<String>,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Direction {
-    Above,
-    Below,
-}
-
-fn counterpoint(notes: &[Pitch], scale: &Scale, direction: Direction) -> Option<Vec<Pitch>> {
-    // The first note must be a perfect octave, unison, or fifth.
-    
-    let mut opening_pitches = if direction == Direction::Above {
-        vec![notes[0] + Interval::Unison, notes[0] + Interval::PerfectFifth, notes[0] + 12]
-    } else {
-        vec![notes[0] - Interval::Unison, notes[0] - Interval::PerfectFifth, notes[0] - 12]
-    };
-    
-
-    // We want only notes in the scale.
-    let scale_notes = scale.notes();
-    for idx in (0..opening_pitches.len()).into_iter().rev() {
-        if !scale_notes.contains(&opening_pitches[idx].0) {
-            opening_pitches.remove(idx);
-        }
+/// Finds the named scale type with the given name, ignoring case, spaces, and hyphens, so `melodic-minor` and
+/// `MelodicMinor` both find the melodic minor scale. `major` and `minor` are accepted for Ionian and Aeolian.
+fn parse_scale_type(name: &str) -> Option<ScaleType> {
+    let squash = |name: &str| name.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    let name = squash(name);
+    match name.as_str() {
+        "major" => Some(ScaleType::Ionian),
+        "minor" => Some(ScaleType::Aeolian),
+        _ => ScaleType::all().iter().find(|scale_type| squash(&scale_type.to_string()) == name).cloned(),
     }
-
-    shuffle(&mut opening_pitches);
-
-    for opening in opening_pitches {
-        let res = counterpoint_helper(notes, &vec![opening], scale, direction);
-        if res.is_some() {
-            return res;
-        }
-    }
-    None
 }
 
-fn counterpoint_helper(notes: &[Pitch], so_far: &[Pitch], scale: &Scale, direction: Direction) -> Option<Vec<Pitch>> {
-    if so_far.len() == notes.len() {
-        return Some(Vec::from(so_far))
-    }
-
-    let other_note = notes[so_far.len()];
-
-    // If this is the ending, we must choose a unison or octave.
-    let mut options = if so_far.len() == notes.len() - 1 {
-        if direction == Direction::Above {
-            vec![other_note + Interval::Unison, other_note + 12]
-        } else {
-            vec![other_note - Interval::Unison, other_note - 12]
-        }
-    } else {
-        // Otherwise, we want a consonant interval.
-        if direction == Direction::Above {
-            vec![other_note + Interval::PerfectFifth, other_note + Interval::MinorThird, other_note + Interval::MajorThird, other_note + Interval::MinorSixth, other_note + Interval::MajorSixth, other_note + 12, other_note + 12 + Interval::MinorThird, other_note + 12 + Interval::MajorThird]
-        } else {
-            vec![other_note - Interval::PerfectFifth, other_note - Interval::MinorThird, other_note - Interval::MajorThird, other_note - Interval::MinorSixth, other_note - Interval::MajorSixth, other_note - 12, other_note - 12 + Interval::MinorThird, other_note - 12 - Interval::MajorThird]
-        }
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        input: None,
+        scale: Scale(Note::C, ScaleType::Ionian),
+        direction: Direction::Below,
+        budget: 1_000_000,
+        format: Format::Text,
+        output: None,
     };
 
-    // We only want notes from the scale.
-    let scale_notes = scale.notes();
-    for idx in (0..options.len()).into_iter().rev() {
-        if !scale_notes.contains(&options[idx].0) {
-            options.remove(idx);
-        }
-    }
-
-    // We don't want direct or parallel fifths or octaves.
-    for idx in (0..options.len()).into_iter().rev() {
-        let option = options[idx];
-        if option - other_note == Interval::PerfectFifth || option - other_note == Interval::Unison {
-            let prev_note = so_far[so_far.len() - 1];
-            let other_prev_note = notes[so_far.len() - 1];
-
-            let motion = option.semitones_from_middle_c() - prev_note.semitones_from_middle_c();
-            let other_motion = other_note.semitones_from_middle_c() - other_prev_note.semitones_from_middle_c();
-
-            if sign(motion) == sign(other_motion) {
-                options.remove(idx);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} expects a value", name));
+        match arg.as_str() {
+            "--input" => {
+                let input = value("--input")?;
+                options.input = if input == "-" { None } else { Some(input) };
             }
-        }
-    }
-
-    // Don't exceed a tenth from the other line
-    for idx in (0..options.len()).into_iter().rev() {
-        let option = options[idx].semitones_from_middle_c();
-        let other = other_note.semitones_from_middle_c();
-        if (option - other).abs() as u8 > 12 + Interval::MajorThird.semitones() {
-            options.remove(idx);
-        }
-    }
-
-    // Don't move in parallel sixths or thirds more than three notes at a time.
-    for idx in (0..options.len()).into_iter().rev() {
-        let interval = options[idx] - other_note;
-        let mut count = 1;
-        if interval == Interval::MinorThird || interval == Interval::MajorThird {
-            for m_idx in (0..so_far.len()).into_iter().rev() {
-                let interval = so_far[m_idx] - notes[m_idx];
-                if interval != Interval::MinorThird && interval != Interval::MajorThird {
-                    break;
-                } else {
-                    count += 1;
+            "--scale" => {
+                let root = value("--scale")?;
+                let root = root.parse().map_err(|err| format!("Couldn't read the scale root '{}': {}", root, err))?;
+                let name = value("--scale")?;
+                let scale_type = parse_scale_type(&name).ok_or_else(|| format!("Unknown scale type '{}'", name))?;
+                options.scale = Scale(root, scale_type);
+            }
+            "--direction" => {
+                options.direction = match value("--direction")?.to_ascii_lowercase().as_str() {
+                    "above" => Direction::Above,
+                    "below" => Direction::Below,
+                    other => return Err(format!("Expected a direction of above or below but found '{}'", other)),
                 }
             }
-        } else if interval == Interval::MinorSixth || interval == Interval::MajorSixth {
-            for m_idx in (0..so_far.len()).into_iter().rev() {
-                let interval = so_far[m_idx] - notes[m_idx];
-                if interval != Interval::MinorSixth && interval != Interval::MajorSixth {
-                    break;
-                } else {
-                    count += 1;
+            "--budget" => {
+                let budget = value("--budget")?;
+                options.budget = budget.parse().map_err(|_| format!("Expected a number of steps but found '{}'", budget))?;
+            }
+            "--format" => {
+                options.format = match value("--format")?.to_ascii_lowercase().as_str() {
+                    "text" => Format::Text,
+                    "midi" => Format::Midi,
+                    "wav" => Format::Wav,
+                    "lilypond" => Format::LilyPond,
+                    "abc" => Format::Abc,
+                    "musicxml" => Format::MusicXml,
+                    other => return Err(format!("Expected a format of text, midi, wav, lilypond, abc, or musicxml but found '{}'", other)),
                 }
             }
-        }
-        if count > 3 {
-            options.remove(idx);
+            "--output" => options.output = Some(value("--output")?),
+            other => return Err(format!("Unexpected argument '{}'", other)),
         }
     }
+    Ok(options)
+}
 
-    // Don't have both voices skip in the same direction
-    for idx in (0..options.len()).into_iter().rev() {
-        let option = options[idx];
-        let prev_note = so_far[so_far.len() - 1];
-
-        let is_skip = (option.semitones_from_middle_c() - prev_note.semitones_from_middle_c()).abs() as u8 > Interval::MajorSecond.semitones();
-
-        let other_prev_note = notes[so_far.len() - 1];
-        let is_other_skip = (other_note.semitones_from_middle_c() - other_prev_note.semitones_from_middle_c()).abs() as u8 > Interval::MajorSecond.semitones();
-
-        if is_skip && is_other_skip {
-            let motion = option.semitones_from_middle_c() - prev_note.semitones_from_middle_c();
-            let other_motion = other_note.semitones_from_middle_c() - other_prev_note.semitones_from_middle_c();
-
-            if sign(motion) == sign(other_motion) {
-                options.remove(idx);
-            }
+/// Reads the whole of the given file, or of stdin if there isn't one.
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut data = String::new();
+            io::stdin().read_to_string(&mut data)?;
+            Ok(data)
         }
     }
+}
 
-    // Don't repeat the same note more than twice
-    for idx in (0..options.len()).into_iter().rev() {
-        if so_far.len() > 1 {
-            if options[idx].0 == so_far[so_far.len() - 1].0 && so_far[so_far.len() - 1].0 == so_far[so_far.len() - 2].0 {
-                options.remove(idx);
-            }
-        }
+/// Lays out the cantus above the counterpoint, with each pitch padded to the width of the longest so that the pitches
+/// sounding together line up in columns. Widths are counted in characters rather than bytes, since accidentals like ♯
+/// take up several bytes but a single column.
+fn format_score(cantus: &[Pitch], counter: &[Pitch]) -> String {
+    let rows: Vec<Vec<String>> = [cantus, counter].iter().map(|voice| voice.iter().map(|pitch| pitch.to_string()).collect()).collect();
+    let width = rows.iter().flatten().map(|pitch| pitch.chars().count()).max().unwrap_or(0);
+
+    let mut score = String::new();
+    for row in rows {
+        let padded: Vec<String> = row.iter().map(|pitch| format!("{:width$}", pitch, width = width)).collect();
+        score.push_str(padded.join(" ").trim_end());
+        score.push('\n');
     }
+    score
+}
 
+/// Renders the cantus and the counterpoint in the given format.
+fn render(format: Format, cantus: &[Pitch], counter: &[Pitch]) -> io::Result<Vec<u8>> {
+    let voices = [cantus, counter];
+    let data = match format {
+        Format::Text => format_score(cantus, counter).into_bytes(),
+        Format::Midi => midi::to_midi(&voices, 120)?,
+        Format::Wav => wav::to_wav(&voices, 44_100, 0.5)?,
+        Format::LilyPond => lilypond::to_lilypond(&voices).into_bytes(),
+        Format::Abc => abc::to_abc(&voices).into_bytes(),
+        Format::MusicXml => musicxml::to_musicxml(&voices).into_bytes(),
+    };
+    Ok(data)
+}
 
-    // Don't leap more than an octave
-    for idx in (0..options.len()).into_iter().rev() {
-        let option = options[idx];
-        let prev_note = so_far[so_far.len() - 1];
-        let leap = (option.semitones_from_middle_c() - prev_note.semitones_from_middle_c()).abs() as u8;
-        if leap > 12 {
-            options.remove(idx);
-        }
+/// Writes the data to the given file, or to stdout if there isn't one.
+fn write_output(path: Option<&str>, data: &[u8]) -> io::Result<()> {
+    match path {
+        Some(path) => fs::write(path, data),
+        None => io::stdout().write_all(data),
     }
+}
 
-    // Don't leap by a tritone
-    for idx in (0..options.len()).into_iter().rev() {
-        let option = options[idx];
-        let prev_note = so_far[so_far.len() - 1];
-        let leap = (option.semitones_from_middle_c() - prev_note.semitones_from_middle_c()).abs() as u8;
-        if leap == Interval::Tritone.semitones() {
-            options.remove(idx);
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("Error: {}\n{}", err, USAGE);
+            process::exit(1);
         }
-    }
-
-    // Approach the last note via stepwise motion
-    if so_far.len() == notes.len() - 1 {
-        for idx in (0..options.len()).into_iter().rev() {
-            let option = options[idx];
-            let prev_note = so_far[so_far.len() - 1];
-            let leap = (option.semitones_from_middle_c() - prev_note.semitones_from_middle_c()).abs() as u8;
-            if leap > Interval::MajorSecond.semitones() {
-                options.remove(idx);
-            }
+    };
+    let data = match read_input(options.input.as_deref()) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Error: Couldn't open {}: {}", options.input.as_deref().unwrap_or("stdin"), err);
+            process::exit(1);
         }
-    }
-
-    // If you leap, you must go the opposite direction by step
-    for idx in (0..options.len()).into_iter().rev() {
-        let option = options[idx];
-        let prev_note = so_far[so_far.len() - 1];
-        if so_far.len() > 1 {
-            let prev_prev_note = so_far[so_far.len() - 2];
-
-            let motion = prev_note.semitones_from_middle_c() - prev_prev_note.semitones_from_middle_c();
-            if motion.abs() as u8 > Interval::MajorThird.semitones() {
-                let curr_motion = option.semitones_from_middle_c() - prev_note.semitones_from_middle_c();
-                if curr_motion.abs() as u8 > Interval::MajorSecond.semitones() || sign(curr_motion) == sign(motion) {
-                    options.remove(idx);
-                }
-            }
+    };
+    let cantus_firmus = match parse_music(&data) {
+        Ok(cantus_firmus) => cantus_firmus,
+        Err(err) => {
+            eprintln!("Error: Couldn't read the cantus firmus: {}", err);
+            process::exit(1);
         }
-    }
-
-
-    shuffle(&mut options);
-
-    for option in options {
-        let mut r = Vec::from(so_far);
-        r.push(option);
-
-        let res = counterpoint_helper(notes, &r, scale, direction);
-        if res.is_some() {
-            return res;
+    };
+    if cantus_firmus.is_empty() {
+        eprintln!("Error: The cantus firmus has no notes\n{}", USAGE);
+        process::exit(1);
+    }
+    match counterpoint_with_budget(&cantus_firmus, &options.scale, options.direction, &RuleSet::strict_fux(), options.budget, &mut rand::thread_rng()) {
+        Ok(notes) => {
+            let result = render(options.format, &cantus_firmus, &notes).and_then(|data| write_output(options.output.as_deref(), &data));
+            if let Err(err) = result {
+                eprintln!("Error: Couldn't write {}: {}", options.output.as_deref().unwrap_or("stdout"), err);
+                process::exit(1);
+            }
         }
-    }
-    None
-}
-
-fn parse_music(data: &mut std::str::Chars) -> Vec<Pitch> {
-    let mut result = vec![];
-
-    loop {
-        let mut c = data.next();
-
-        while c.map_or(false, |f| { f.is_ascii_whitespace() }) {
-            c = data.next();
+        Err(SearchError::NoSolution) => {
+            eprintln!("Error: No counterpoint :(");
+            process::exit(1);
         }
-
-        if let Some(c) = c {
-            let pitch_base = match c.to_ascii_lowercase() {
-                'a' => PitchBase::A,
-                'b' => PitchBase::B,
-                'c' => PitchBase::C,
-                'd' => PitchBase::D,
-                'e' => PitchBase::E,
-                'f' => PitchBase::F,
-                'g' => PitchBase::G,
-                _ => panic!("Unexpected pitch base")
-            };
-
-            let mut c = data.next().expect("Unexpected end of file");
-            let pitch_modifier = if !c.is_numeric() {
-                let res = match c {
-                    '#' => PitchModifier::Sharp,
-                    'b' => PitchModifier::Flat,
-                    _ => panic!("Unexpected pitch modifier")
-                };
-                c = data.next().expect("Unexpected end of file");
-                res
-            } else {
-                PitchModifier::Natural
-            };
-
-            let octave = match c {
-                '0' => 0,
-                '1' => 1,
-                '2' => 2,
-                '3' => 3,
-                '4' => 4,
-                '5' => 5,
-                '6' => 6,
-                '7' => 7,
-                '8' => 8,
-                _ => panic!("Unexpected octave value")
-            };
-
-            result.push(Pitch(Note(pitch_base, pitch_modifier), octave));
-        } else {
-            break;
+        Err(err @ SearchError::OutOfBudget) => {
+            eprintln!("Error: Gave up, {}. Try a larger --budget.", err);
+            process::exit(1);
         }
     }
-    result
 }
 
-fn main() {
-    let cantus_firmus = include_str!("../cantus.txt");
-    let cantus_firmus = parse_music(&mut cantus_firmus.chars());
-    if let Some(notes) = counterpoint(&cantus_firmus, &Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::Ionian), Direction::Below) {
-        for note in cantus_firmus {
-            print!("{} ", note);
-        }
-        println!();
-        for note in notes {
-            print!("{} ", note);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        let cantus = parse_music("D4 F4 E4 D4").unwrap();
+        let counter = parse_music("D3 D3 C3 D3").unwrap();
+        for format in &[Format::Text, Format::Midi, Format::Wav, Format::LilyPond, Format::Abc, Format::MusicXml] {
+            assert!(!render(*format, &cantus, &counter).unwrap().is_empty(), "{:?}", format);
+        }
+        // Text stays as two lines of pitches
+        assert_eq!(render(Format::Text, &cantus, &counter).unwrap(), b"D4 F4 E4 D4\nD3 D3 C3 D3\n".to_vec());
+        assert!(render(Format::Midi, &cantus, &counter).unwrap().starts_with(b"MThd"));
+        assert!(render(Format::Wav, &cantus, &counter).unwrap().starts_with(b"RIFF"));
+    }
+
+    #[test]
+    fn aligned_score() {
+        let cantus = parse_music("D4 F#4 E4 D4").unwrap();
+        let counter = parse_music("D3 D3 C##3 B2").unwrap();
+        let score = format_score(&cantus, &counter);
+        assert_eq!(score, "D4  F♯4 E4  D4\nD3  D3  C𝄪3 B2\n");
+        // Each pitch starts in the same column as the one it sounds against
+        let lines: Vec<Vec<char>> = score.lines().map(|line| line.chars().collect()).collect();
+        for (idx, c) in lines[0].iter().enumerate() {
+            assert_eq!(c.is_alphabetic(), lines[1][idx].is_alphabetic());
+        }
+        // Without any accidentals, the columns are as narrow as the pitches
+        assert_eq!(format_score(&parse_music("C4 D4").unwrap(), &parse_music("A3 B3").unwrap()), "C4 D4\nA3 B3\n");
+        assert_eq!(format_score(&[], &[]), "\n\n");
+    }
+
+    #[test]
+    fn format_arguments() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(args(&[]).unwrap().format, Format::Text);
+        assert_eq!(args(&["--format", "LilyPond"]).unwrap().format, Format::LilyPond);
+        let options = args(&["--format", "midi", "--output", "out.mid"]).unwrap();
+        assert_eq!((options.format, options.output), (Format::Midi, Some("out.mid".to_string())));
+        assert!(args(&["--format", "pdf"]).is_err());
+        assert!(args(&["--output"]).is_err());
+    }
+
+    #[test]
+    fn scale_arguments() {
+        let scale = |name: &str| parse_args(["--scale", "D", name].iter().map(|arg| arg.to_string())).map(|options| options.scale.1);
+        assert_eq!(scale("Dorian"), Ok(ScaleType::Dorian));
+        assert_eq!(scale("melodic-minor"), Ok(ScaleType::MelodicMinor));
+        assert_eq!(scale("HalfWholeDiminished"), Ok(ScaleType::DiminishedHalfWhole));
+        assert_eq!(scale("minor"), Ok(ScaleType::Aeolian));
+        assert!(scale("bebop").is_err());
+        // Every named scale can be asked for by the name it's displayed with
+        for scale_type in ScaleType::all() {
+            assert_eq!(parse_scale_type(&scale_type.to_string()).as_ref(), Some(scale_type));
         }
-        println!();
-    } else {
-        println!("Error: No counterpoint :(");
     }
 }
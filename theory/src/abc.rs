@@ -0,0 +1,90 @@
+//! Writing pitches out in ABC notation, a plain-text format read by tools such as abcjs and abcm2ps.
+
+use crate::{Pitch, PitchModifier};
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+/// The number of quarter notes in each measure.
+const BEATS_PER_MEASURE: usize = 4;
+
+/// Renders the voices as an ABC tune with one voice per line. Every pitch is written as a quarter note in 4/4 time,
+/// matching `midi::write_midi`.
+pub fn to_abc(voices: &[&[Pitch]]) -> String {
+    let mut abc = String::new();
+    abc.push_str("X:1\n");
+    abc.push_str("T:Counterpoint\n");
+    writeln!(abc, "M:{}/4", BEATS_PER_MEASURE).unwrap();
+    abc.push_str("L:1/4\n");
+    abc.push_str("K:C\n");
+
+    for (idx, voice) in voices.iter().enumerate() {
+        // Voices that mostly lie below middle C get a bass clef
+        let total: i32 = voice.iter().map(|pitch| pitch.semitones_from_middle_c() as i32).sum();
+        let clef = if total < 0 { "bass" } else { "treble" };
+        writeln!(abc, "V:{} clef={}", idx + 1, clef).unwrap();
+
+        let measures: Vec<String> = voice.chunks(BEATS_PER_MEASURE).map(write_measure).collect();
+        writeln!(abc, "{} |]", measures.join(" | ")).unwrap();
+    }
+    abc
+}
+
+/// Writes the notes of one measure. An accidental lasts until the end of the measure, so one is only written when the
+/// note differs from the last on the same line and space, with `=` cancelling an earlier one.
+fn write_measure(measure: &[Pitch]) -> String {
+    let mut notes = vec![];
+    for (idx, pitch) in measure.iter().enumerate() {
+        // Pitches on the same letter and octave only sound the same if they have the same accidental
+        let previous = measure[..idx].iter().rev().find(|other| (other.0).0.steps_from_c() == (pitch.0).0.steps_from_c() && other.1 == pitch.1);
+        let accidental = match (previous, (pitch.0).1) {
+            (Some(previous), _) if previous == pitch => "",
+            (None, PitchModifier::Natural) => "",
+            (Some(_), PitchModifier::Natural) => "=",
+            (_, PitchModifier::DoubleFlat) => "__",
+            (_, PitchModifier::Flat) => "_",
+            (_, PitchModifier::Sharp) => "^",
+            (_, PitchModifier::DoubleSharp) => "^^",
+        };
+        notes.push(format!("{}{}", accidental, pitch_name(pitch)));
+    }
+    notes.join(" ")
+}
+
+/// Names a pitch's letter and octave, where `C` is C4, `c` is C5, and each `'` or `,` moves up or down an octave.
+fn pitch_name(pitch: &Pitch) -> String {
+    let letter = format!("{}", (pitch.0).0);
+    if (pitch.1).0 >= 5 {
+        format!("{}{}", letter.to_lowercase(), "'".repeat(((pitch.1).0 - 5) as usize))
+    } else {
+        format!("{}{}", letter, ",".repeat((4 - (pitch.1).0) as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn voices_and_notes() {
+        let cantus = parse_music("D4 F#4 E4 D4 C5").unwrap();
+        let counterpoint = parse_music("D3 D3 C##3 B2 A3").unwrap();
+        let abc = to_abc(&[&cantus, &counterpoint]);
+
+        assert!(abc.starts_with("X:1\n"));
+        assert!(abc.contains("L:1/4\nK:C\n"));
+        assert!(abc.contains("V:1 clef=treble\nD ^F E D | c |]\n"));
+        assert!(abc.contains("V:2 clef=bass\nD, D, ^^C, B,, | A, |]\n"));
+    }
+
+    #[test]
+    fn accidentals_last_the_measure() {
+        let line = |s: &str| write_measure(&parse_music(s).unwrap());
+        // The second F♯ doesn't need its sharp again, but F natural needs cancelling
+        assert_eq!(line("F#4 F#4 F4 F4"), "^F F =F F");
+        // Other octaves aren't affected
+        assert_eq!(line("Bb3 B4 Bb3 B3"), "_B, B B, =B,");
+        assert_eq!(line("C6 Cb6"), "c' _c'");
+    }
+}
@@ -0,0 +1,1819 @@
+//! Generating and checking species counterpoint against a cantus firmus.
+
+use crate::{is_strong_beat, motion_type, DirectedInterval, Interval, IntervalQuality, Motion, Pitch, Range, Scale};
+use rand::prelude::*;
+use std::fmt;
+
+/// Shuffles uniformly with the Fisher-Yates algorithm, where each element may end up anywhere, including where it
+/// started.
+fn shuffle<T, R: Rng + ?Sized>(val: &mut [T], rng: &mut R) {
+    for i in (1..val.len()).rev() {
+        let idx = rng.gen_range(0..=i);
+        val.swap(i, idx)
+    }
+}
+
+/// Shuffles so that each element is more likely to come first the heavier it's weighted, by giving each a random key of
+/// `u^(1/weight)` and sorting by it. Weights must be positive.
+fn weighted_shuffle<T, R: Rng + ?Sized>(val: &mut Vec<T>, weight: impl Fn(&T) -> f64, rng: &mut R) {
+    let mut keyed: Vec<(f64, T)> = val.drain(..).map(|item| (rng.gen::<f64>().powf(1.0 / weight(&item)), item)).collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    val.extend(keyed.into_iter().map(|(_, item)| item));
+}
+
+/// Weighs how idiomatic it would be for the counterpoint to move from `prev` to `candidate` against `cantus`. Steps are
+/// favored over thirds, thirds over larger leaps, and repeated notes least of all, and imperfect consonances count for
+/// twice as much as perfect ones.
+pub fn weight_option(prev: Pitch, candidate: Pitch, cantus: Pitch) -> f64 {
+    let motion = (candidate.semitones_from_middle_c() - prev.semitones_from_middle_c()).unsigned_abs();
+    let melodic = if motion == 0 {
+        0.5
+    } else if motion <= Interval::MAJOR_SECOND.semitones() as u16 {
+        4.0
+    } else if motion <= Interval::MAJOR_THIRD.semitones() as u16 {
+        2.0
+    } else {
+        1.0
+    };
+    let harmonic = if (candidate - cantus).is_imperfect_consonance() { 2.0 } else { 1.0 };
+    melodic * harmonic
+}
+
+/// Whether two voices move into a perfect fifth or octave by similar or parallel motion. Compound intervals count too,
+/// so moving into a twelfth or a fifteenth is also caught.
+fn is_direct_perfect(prev: Pitch, cur: Pitch, other_prev: Pitch, other_cur: Pitch) -> bool {
+    if !(cur - other_cur).is_perfect_consonance() {
+        return false;
+    }
+
+    matches!(motion_type(prev, cur, other_prev, other_cur), Motion::Parallel | Motion::Similar)
+}
+
+/// Whether a voice leaps into a perfect consonance while the other voice steps in the opposite direction, known as the
+/// battuta.
+fn is_battuta(prev: Pitch, cur: Pitch, other_prev: Pitch, other_cur: Pitch) -> bool {
+    let is_leap = prev.distance_to(&cur).unsigned_abs() > Interval::MAJOR_SECOND.semitones() as u16;
+    let other_step = other_prev.distance_to(&other_cur).unsigned_abs();
+    let is_other_step = (1..=Interval::MAJOR_SECOND.semitones() as u16).contains(&other_step);
+    (cur - other_cur).is_perfect_consonance() && is_leap && is_other_step && motion_type(prev, cur, other_prev, other_cur) == Motion::Contrary
+}
+
+/// Whether both voices skip in the same direction.
+fn is_similar_skip(prev: Pitch, cur: Pitch, other_prev: Pitch, other_cur: Pitch) -> bool {
+    let is_skip = (cur.semitones_from_middle_c() - prev.semitones_from_middle_c()).unsigned_abs() > Interval::MAJOR_SECOND.semitones() as u16;
+    let is_other_skip = (other_cur.semitones_from_middle_c() - other_prev.semitones_from_middle_c()).unsigned_abs() > Interval::MAJOR_SECOND.semitones() as u16;
+    is_skip && is_other_skip && matches!(motion_type(prev, cur, other_prev, other_cur), Motion::Parallel | Motion::Similar)
+}
+
+/// Whether moving from one pitch to the next is by an augmented or diminished interval, going by the letter names.
+fn is_augmented_or_diminished(from: Pitch, to: Pitch) -> bool {
+    let quality = DirectedInterval::between(from, to).interval().0;
+    matches!(quality, IntervalQuality::Augmented | IntervalQuality::Diminished | IntervalQuality::DoublyAugmented | IntervalQuality::DoublyDiminished)
+}
+
+/// Whether two simultaneous pitches form a consonance, as judged by `Interval::is_consonant`.
+fn is_consonant(a: Pitch, b: Pitch) -> bool {
+    (a - b).is_consonant()
+}
+
+/// Whether two simultaneous pitches are consonant given where they sit in the texture. A perfect fourth is dissonant
+/// against the lowest sounding voice but consonant between two voices above it; everything else is judged as by
+/// `is_consonant`.
+pub fn interval_is_consonant_in_context(lower: Pitch, upper: Pitch, is_lowest_pair: bool) -> bool {
+    let interval = upper - lower;
+    interval.is_consonant() || (!is_lowest_pair && interval.simple() == Interval::PERFECT_FOURTH)
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Whether the counterpoint is written above or below the cantus firmus
+pub enum Direction {
+    Above,
+    Below,
+}
+
+/// The rules the solver follows when writing counterpoint, so that they can be relaxed or tightened per exercise.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuleSet {
+    /// The widest the counterpoint may get from the other line.
+    pub max_span: Interval,
+    /// The largest a single melodic leap may be.
+    pub max_leap: Interval,
+    /// The most times in a row the same pitch may sound. Pitches are compared by how they sound, so B♯3 repeats C4,
+    /// but the same note in another octave doesn't count as a repeat.
+    pub max_consecutive_repeats: u8,
+    /// The most notes in a row that may move in parallel thirds or in parallel sixths.
+    pub max_parallel_imperfects: u8,
+    /// Forbid moving into a fifth by parallel or similar motion.
+    pub forbid_parallel_fifths: bool,
+    /// Forbid moving into an octave or unison by parallel or similar motion.
+    pub forbid_parallel_octaves: bool,
+    /// Forbid both voices from skipping in the same direction.
+    pub forbid_similar_skips: bool,
+    /// Forbid leaping by a tritone.
+    pub forbid_tritone_leaps: bool,
+    /// Forbid moving by an augmented or diminished interval, as spelled, such as the augmented second between the
+    /// sixth and seventh degrees of harmonic minor.
+    pub forbid_augmented_diminished_motion: bool,
+    /// Require the last note to be approached by step.
+    pub step_to_final: bool,
+    /// Require a leap larger than a third to be followed by a step in the opposite direction.
+    pub recover_leaps: bool,
+    /// The range the counterpoint must stay within, if any.
+    pub range: Option<Range>,
+    /// When generating at random, try the options `weight_option` favors first instead of picking uniformly, for
+    /// smoother lines with more imperfect consonances.
+    pub weighted_choice: bool,
+    /// Allow the counterpoint to cross to the other side of the cantus firmus between the opening and the close.
+    pub allow_voice_crossing: bool,
+    /// Allow the counterpoint to meet the cantus firmus in a unison between the opening and the close. Upbeats in
+    /// second species may touch a unison either way.
+    pub allow_interior_unison: bool,
+    /// Require the line to reach its highest note only once, as checked by `climax`.
+    pub single_climax: bool,
+    /// Try options that move in contrary motion to the other line before any others, for more idiomatic lines. The
+    /// options are otherwise still ordered at random or by weight.
+    pub prefer_contrary_motion: bool,
+    /// Only use notes from the scale. Without this, the counterpoint is free to use any note that the other rules
+    /// allow, such as chromatic passing tones.
+    pub restrict_to_scale: bool,
+    /// Allow a dissonant upbeat in second species to be a neighbor tone, stepping away from the downbeat and back,
+    /// rather than only a passing tone.
+    pub allow_neighbor_tones: bool,
+    /// Forbid the battuta, leaping into a perfect consonance while the other line steps the opposite way, as in
+    /// stricter Renaissance practice.
+    pub forbid_battuta: bool,
+    /// The intervals the counterpoint may open on against the first note of the other line, measured away from it in
+    /// the direction of the counterpoint.
+    pub opening_intervals: Vec<Interval>,
+}
+
+impl RuleSet {
+    /// The strict rules of first-species counterpoint as laid out by Fux.
+    pub fn strict_fux() -> Self {
+        RuleSet {
+            max_span: Interval(IntervalQuality::Major, 10),
+            max_leap: Interval::OCTAVE,
+            max_consecutive_repeats: 2,
+            max_parallel_imperfects: 3,
+            forbid_parallel_fifths: true,
+            forbid_parallel_octaves: true,
+            forbid_similar_skips: true,
+            forbid_tritone_leaps: true,
+            forbid_augmented_diminished_motion: true,
+            step_to_final: true,
+            recover_leaps: true,
+            range: None,
+            weighted_choice: false,
+            allow_voice_crossing: false,
+            allow_interior_unison: false,
+            single_climax: false,
+            prefer_contrary_motion: false,
+            restrict_to_scale: true,
+            allow_neighbor_tones: false,
+            forbid_battuta: false,
+            opening_intervals: vec![Interval::UNISON, Interval::PERFECT_FIFTH, Interval::OCTAVE],
+        }
+    }
+
+    /// Whether the pitch is in the scale, or may be used anyway because the rules don't restrict notes to the scale.
+    fn is_in_scale(&self, scale: &Scale, pitch: Pitch) -> bool {
+        !self.restrict_to_scale || scale.contains_pitch(pitch)
+    }
+
+    /// Whether the pitch lies within the range, if there is one.
+    fn is_in_range(&self, pitch: Pitch) -> bool {
+        self.range.is_none_or(|range| range.contains(pitch))
+    }
+
+    /// Whether moving into `cur` against `other_cur` makes forbidden parallel or direct fifths or octaves.
+    fn is_forbidden_perfect(&self, prev: Pitch, cur: Pitch, other_prev: Pitch, other_cur: Pitch) -> bool {
+        let interval = (cur - other_cur).simple();
+        let is_forbidden = (self.forbid_parallel_fifths && interval == Interval::PERFECT_FIFTH)
+            || (self.forbid_parallel_octaves && interval == Interval::UNISON);
+        is_forbidden && is_direct_perfect(prev, cur, other_prev, other_cur)
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet::strict_fux()
+    }
+}
+
+/// The species of counterpoint, which sets how many notes of counterpoint go against each note of the cantus firmus.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Species {
+    /// One note against each note of the cantus.
+    First,
+    /// Two notes against each note of the cantus, except the last which is held.
+    Second,
+}
+
+/// Generates a line of first-species counterpoint against the cantus firmus, on the given side of it, that follows the
+/// rules. The options at each note are tried in an order drawn from `rng`, so a seeded generator always finds the same
+/// line. Returns `None` if no line follows the rules.
+pub fn counterpoint(notes: &[Pitch], scale: &Scale, direction: Direction, rules: &RuleSet, rng: &mut impl Rng) -> Option<Vec<Pitch>> {
+    Solutions::new(notes, scale, direction, rules, Some(rng)).next()
+}
+
+/// Why a search with a budget didn't find a line of counterpoint.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum SearchError {
+    /// Every possibility was tried, so there's no line that follows the rules.
+    NoSolution,
+    /// The search gave up after trying as many notes as its budget allowed.
+    OutOfBudget,
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchError::NoSolution => write!(f, "no counterpoint follows the rules"),
+            SearchError::OutOfBudget => write!(f, "the search ran out of steps before finding any counterpoint"),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+/// Generates counterpoint like `counterpoint`, but gives up after trying `max_steps` notes so that long or impossible
+/// exercises can't keep the search running indefinitely.
+pub fn counterpoint_with_budget(notes: &[Pitch], scale: &Scale, direction: Direction, rules: &RuleSet, max_steps: usize, rng: &mut impl Rng) -> Result<Vec<Pitch>, SearchError> {
+    let mut search = Solutions::new(notes, scale, direction, rules, Some(rng));
+    search.steps_left = Some(max_steps);
+    match search.next() {
+        Some(line) => Ok(line),
+        None if search.is_out_of_budget() => Err(SearchError::OutOfBudget),
+        None => Err(SearchError::NoSolution),
+    }
+}
+
+/// Statistics about how much work a search for counterpoint did, for understanding why some cantus firmi are slow or
+/// have no solution.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct SolveStats {
+    /// How many notes were tried.
+    pub nodes: usize,
+    /// How many times the search reached a note that nothing could follow, and had to backtrack.
+    pub dead_ends: usize,
+    /// The most notes of the line that were ever written at once.
+    pub max_depth: usize,
+}
+
+/// Generates counterpoint like `counterpoint`, also reporting how much searching it took whether or not a line was
+/// found.
+pub fn counterpoint_with_stats(notes: &[Pitch], scale: &Scale, direction: Direction, rules: &RuleSet, rng: &mut impl Rng) -> (Option<Vec<Pitch>>, SolveStats) {
+    let mut search = Solutions::new(notes, scale, direction, rules, Some(rng));
+    let line = search.next();
+    (line, search.stats)
+}
+
+/// Generates counterpoint on whichever side of the cantus firmus has a solution, trying both in a random order. Returns
+/// the line along with the direction it was written in.
+pub fn counterpoint_either(notes: &[Pitch], scale: &Scale, rules: &RuleSet, rng: &mut impl Rng) -> Option<(Direction, Vec<Pitch>)> {
+    let mut directions = [Direction::Above, Direction::Below];
+    shuffle(&mut directions, rng);
+    directions.iter().find_map(|direction| counterpoint(notes, scale, *direction, rules, rng).map(|line| (*direction, line)))
+}
+
+/// Generates one line of counterpoint above the cantus firmus and one below it, so both can be heard side by side.
+/// Either line is `None` if there is no counterpoint on that side.
+pub fn harmonize(notes: &[Pitch], scale: &Scale, rules: &RuleSet, rng: &mut impl Rng) -> (Option<Vec<Pitch>>, Option<Vec<Pitch>>) {
+    let above = counterpoint(notes, scale, Direction::Above, rules, rng);
+    let below = counterpoint(notes, scale, Direction::Below, rules, rng);
+    (above, below)
+}
+
+/// Scores how smooth a melodic line is, where higher is smoother. Every semitone leapt beyond a step costs a point,
+/// repeated notes cost three, and leaping twice in a row in the same direction costs five.
+pub fn score_line(line: &[Pitch]) -> i32 {
+    let moves: Vec<i32> = line.windows(2).map(|pair| pair[0].distance_to(&pair[1]) as i32).collect();
+    let step = Interval::MAJOR_SECOND.semitones() as i32;
+
+    let mut score = 0;
+    for (idx, &semitones) in moves.iter().enumerate() {
+        if semitones == 0 {
+            score -= 3;
+        }
+        score -= (semitones.abs() - step).max(0);
+        if idx > 0 && semitones.abs() > step && moves[idx - 1].abs() > step && semitones.signum() == moves[idx - 1].signum() {
+            score -= 5;
+        }
+    }
+    score
+}
+
+/// Gets the index of the line's single highest pitch, or `None` if the highest pitch is reached more than once or the
+/// line is empty.
+pub fn climax(line: &[Pitch]) -> Option<usize> {
+    let highest = line.iter().max()?;
+    let mut climaxes = line.iter().enumerate().filter(|(_, pitch)| *pitch == highest);
+    match (climaxes.next(), climaxes.next()) {
+        (Some((idx, _)), None) => Some(idx),
+        _ => None,
+    }
+}
+
+/// Searches for counterpoint for up to `max_steps` notes and returns the smoothest line found, as judged by
+/// `score_line`, rather than the first. Only gives an error if no line at all was found.
+pub fn counterpoint_best(notes: &[Pitch], scale: &Scale, direction: Direction, rules: &RuleSet, max_steps: usize, rng: &mut impl Rng) -> Result<Vec<Pitch>, SearchError> {
+    let mut search = Solutions::new(notes, scale, direction, rules, Some(rng));
+    search.steps_left = Some(max_steps);
+    let best = search.by_ref().max_by_key(|line| score_line(line));
+    match best {
+        Some(line) => Ok(line),
+        None if search.is_out_of_budget() => Err(SearchError::OutOfBudget),
+        None => Err(SearchError::NoSolution),
+    }
+}
+
+/// Lazily finds every valid line of counterpoint against the cantus firmus, in a deterministic order.
+pub fn counterpoint_all<'a>(notes: &'a [Pitch], scale: &'a Scale, direction: Direction, rules: &'a RuleSet) -> impl Iterator<Item = Vec<Pitch>> + 'a {
+    Solutions::new(notes, scale, direction, rules, None)
+}
+
+/// Generates counterpoint of the given species against the cantus firmus.
+pub fn counterpoint_species(notes: &[Pitch], scale: &Scale, direction: Direction, species: Species, rules: &RuleSet, rng: &mut impl Rng) -> Option<Vec<Pitch>> {
+    match species {
+        Species::First => counterpoint(notes, scale, direction, rules, rng),
+        Species::Second => {
+            let mut openings = opening_pitches(notes, scale, direction, rules);
+            shuffle(&mut openings, rng);
+
+            for opening in openings {
+                let res = second_species_helper(notes, &[opening], &[], scale, direction, rules, rng);
+                if res.is_some() {
+                    return res;
+                }
+            }
+            None
+        }
+    }
+}
+
+/// Gets the pitches the counterpoint may open on, given the notes of the scale. There are none for an empty cantus.
+fn opening_pitches(notes: &[Pitch], scale: &Scale, direction: Direction, rules: &RuleSet) -> Vec<Pitch> {
+    let first = match notes.first() {
+        Some(first) => *first,
+        None => return vec![],
+    };
+
+    // The first note must be one of the opening intervals, normally a perfect octave, unison, or fifth.
+    let mut opening_pitches: Vec<Pitch> = if direction == Direction::Above {
+        rules.opening_intervals.iter().map(|interval| first + *interval).collect()
+    } else {
+        rules.opening_intervals.iter().map(|interval| first - *interval).collect()
+    };
+
+    // We want only notes in the scale and the range.
+    opening_pitches.retain(|pitch| rules.is_in_scale(scale, *pitch) && rules.is_in_range(*pitch));
+
+    opening_pitches
+}
+
+/// Generates several voices of first-species counterpoint above the cantus firmus. Every pair of voices, not just
+/// each voice against the cantus, must be consonant and avoid parallel or direct fifths and octaves, though since the
+/// cantus is the lowest voice, the upper voices may sound a fourth apart. The opening
+/// and closing sonorities must each be either all unisons and octaves or a complete triad. Gives `None` for an empty
+/// cantus or no voices.
+pub fn counterpoint_multi(cantus: &[Pitch], voices: usize, scale: &Scale, rules: &RuleSet, rng: &mut impl Rng) -> Option<Vec<Vec<Pitch>>> {
+    if cantus.is_empty() || voices == 0 {
+        return None;
+    }
+    let opening_pitches: Vec<Pitch> = TRIAD_INTERVALS
+        .iter()
+        .map(|interval| cantus[0] + interval)
+        .filter(|pitch| rules.is_in_scale(scale, *pitch) && rules.is_in_range(*pitch))
+        .collect();
+
+    // The cantus stays the lowest voice
+    let rules = &RuleSet { allow_voice_crossing: false, ..rules.clone() };
+
+    let mut openings = combinations(&vec![opening_pitches; voices]);
+    openings.retain(|opening| is_complete_sonority(cantus[0], opening) && is_consonant_sonority(opening));
+    shuffle(&mut openings, rng);
+
+    for opening in openings {
+        let lines: Vec<Vec<Pitch>> = opening.iter().map(|pitch| vec![*pitch]).collect();
+        let res = counterpoint_multi_helper(cantus, &lines, scale, rules, rng);
+        if res.is_some() {
+            return res;
+        }
+    }
+    None
+}
+
+fn counterpoint_multi_helper(cantus: &[Pitch], lines: &[Vec<Pitch>], scale: &Scale, rules: &RuleSet, rng: &mut impl Rng) -> Option<Vec<Vec<Pitch>>> {
+    let idx = lines[0].len();
+    if idx == cantus.len() {
+        return Some(lines.to_vec());
+    }
+
+    // Each voice must follow the rules against the cantus on its own...
+    let options: Vec<Vec<Pitch>> = lines.iter().map(|line| candidates(cantus, line, scale, Direction::Above, &TRIAD_INTERVALS, rules)).collect();
+    let mut sonorities = combinations(&options);
+
+    // ...as well as against every other voice.
+    sonorities.retain(|sonority| {
+        if !is_consonant_sonority(sonority) {
+            return false;
+        }
+        if idx == cantus.len() - 1 && !is_complete_sonority(cantus[idx], sonority) {
+            return false;
+        }
+        for a in 0..sonority.len() {
+            for b in (a + 1)..sonority.len() {
+                let (prev_a, prev_b) = (lines[a][idx - 1], lines[b][idx - 1]);
+                if rules.is_forbidden_perfect(prev_a, sonority[a], prev_b, sonority[b])
+                    || (rules.forbid_similar_skips && is_similar_skip(prev_a, sonority[a], prev_b, sonority[b]))
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    });
+    shuffle(&mut sonorities, rng);
+
+    for sonority in sonorities {
+        let mut next = lines.to_vec();
+        for (line, pitch) in next.iter_mut().zip(sonority) {
+            line.push(pitch);
+        }
+
+        let res = counterpoint_multi_helper(cantus, &next, scale, rules, rng);
+        if res.is_some() {
+            return res;
+        }
+    }
+    None
+}
+
+/// The intervals above the cantus that the voices may open and close on.
+const TRIAD_INTERVALS: [Interval; 7] = [
+    Interval::UNISON,
+    Interval::MINOR_THIRD,
+    Interval::MAJOR_THIRD,
+    Interval::PERFECT_FIFTH,
+    Interval::OCTAVE,
+    Interval(IntervalQuality::Minor, 10),
+    Interval(IntervalQuality::Major, 10),
+];
+
+/// Whether the voices above a root are either all unisons and octaves or fill out a complete triad.
+fn is_complete_sonority(root: Pitch, sonority: &[Pitch]) -> bool {
+    let intervals: Vec<Interval> = sonority.iter().map(|pitch| (*pitch - root).simple()).collect();
+    let is_open = intervals.iter().all(|interval| *interval == Interval::UNISON);
+    let has_third = intervals.iter().any(|interval| interval.1 == 3);
+    let has_fifth = intervals.contains(&Interval::PERFECT_FIFTH);
+    is_open || (has_third && has_fifth)
+}
+
+/// Whether every pair of pitches in a sonority sounding above the cantus is consonant. None of them is the lowest voice,
+/// so fourths between them are allowed.
+fn is_consonant_sonority(sonority: &[Pitch]) -> bool {
+    sonority.iter().enumerate().all(|(a, pitch)| sonority[a + 1..].iter().all(|other| interval_is_consonant_in_context(*pitch, *other, false)))
+}
+
+/// Gets every way of picking one pitch from each list of options.
+fn combinations(options: &[Vec<Pitch>]) -> Vec<Vec<Pitch>> {
+    let mut result = vec![vec![]];
+    for choices in options {
+        result = result
+            .iter()
+            .flat_map(|partial| choices.iter().map(move |choice| {
+                let mut next = partial.clone();
+                next.push(*choice);
+                next
+            }))
+            .collect();
+    }
+    result
+}
+
+/// Searches for second-species counterpoint. The downbeats follow the same rules as first species, and the upbeat
+/// between each pair of downbeats is picked once the next downbeat is known.
+fn second_species_helper(notes: &[Pitch], downbeats: &[Pitch], upbeats: &[Pitch], scale: &Scale, direction: Direction, rules: &RuleSet, rng: &mut impl Rng) -> Option<Vec<Pitch>> {
+    if downbeats.len() == notes.len() {
+        // The downbeats fall on the strong beats, with the upbeats, where the dissonances may go, between them
+        let line = (0..downbeats.len() + upbeats.len())
+            .map(|idx| if is_strong_beat(idx, SECOND_SPECIES_TIME) { downbeats[idx / 2] } else { upbeats[idx / 2] })
+            .collect();
+        return Some(line);
+    }
+
+    let mut options = candidates(notes, downbeats, scale, direction, &[Interval::UNISON, Interval::OCTAVE], rules);
+    shuffle(&mut options, rng);
+    if rules.prefer_contrary_motion {
+        let idx = downbeats.len();
+        contrary_first(&mut options, downbeats[idx - 1], notes[idx - 1], notes[idx]);
+    }
+
+    for option in options {
+        let prev = downbeats[downbeats.len() - 1];
+        let is_last = downbeats.len() == notes.len() - 1;
+        let mut upbeat_options = upbeat_candidates(notes[downbeats.len() - 1], prev, option, scale, direction, is_last, rules);
+        shuffle(&mut upbeat_options, rng);
+
+        if let Some(upbeat) = upbeat_options.first() {
+            let mut d = Vec::from(downbeats);
+            d.push(option);
+            let mut u = Vec::from(upbeats);
+            u.push(*upbeat);
+
+            let res = second_species_helper(notes, &d, &u, scale, direction, rules, rng);
+            if res.is_some() {
+                return res;
+            }
+        }
+    }
+    None
+}
+
+/// The time signature of second species, with two half notes against each whole note of the cantus.
+const SECOND_SPECIES_TIME: (u8, u8) = (2, 2);
+
+/// The intervals the upbeat may move by from the downbeat before it.
+const UPBEAT_MOTIONS: [Interval; 9] = [
+    Interval::MINOR_SECOND,
+    Interval::MAJOR_SECOND,
+    Interval::MINOR_THIRD,
+    Interval::MAJOR_THIRD,
+    Interval::PERFECT_FOURTH,
+    Interval::PERFECT_FIFTH,
+    Interval::MINOR_SIXTH,
+    Interval::MAJOR_SIXTH,
+    Interval::OCTAVE,
+];
+
+/// Gets the notes that could sound on the upbeat between the downbeats `prev` and `next` against `other_note`. The
+/// upbeat must either be consonant or be a passing tone approached and left by step in the same direction. If
+/// `is_last` is set, the upbeat must lead into the final note by step.
+fn upbeat_candidates(other_note: Pitch, prev: Pitch, next: Pitch, scale: &Scale, direction: Direction, is_last: bool, rules: &RuleSet) -> Vec<Pitch> {
+    let mut options: Vec<Pitch> = UPBEAT_MOTIONS.iter().flat_map(|interval| vec![prev + interval, prev - interval]).collect();
+
+    // We only want notes from the scale, on the same side of the other line unless the voices may cross.
+    options.retain(|option| {
+        let is_on_side = if direction == Direction::Above { *option >= other_note } else { *option <= other_note };
+        rules.is_in_scale(scale, *option) && (is_on_side || rules.allow_voice_crossing) && rules.is_in_range(*option)
+    });
+
+    // Don't exceed the span from the other line
+    options.retain(|option| (option.semitones_from_middle_c() - other_note.semitones_from_middle_c()).unsigned_abs() <= rules.max_span.semitones() as u16);
+
+    // Move on to the next downbeat without repeating it, leaping too far, or leaping by a tritone
+    options.retain(|option| {
+        let leap = (next.semitones_from_middle_c() - option.semitones_from_middle_c()).unsigned_abs();
+        let is_tritone = rules.forbid_tritone_leaps && leap == Interval::AUGMENTED_FOURTH.semitones() as u16;
+        leap != 0 && leap <= rules.max_leap.semitones() as u16 && !is_tritone
+    });
+
+    // Don't move into or out of the upbeat by an augmented or diminished interval
+    if rules.forbid_augmented_diminished_motion {
+        options.retain(|option| !is_augmented_or_diminished(prev, *option) && !is_augmented_or_diminished(*option, next));
+    }
+
+    // Approach the last note via stepwise motion
+    if is_last && rules.step_to_final {
+        options.retain(|option| (next.semitones_from_middle_c() - option.semitones_from_middle_c()).unsigned_abs() <= Interval::MAJOR_SECOND.semitones() as u16);
+    }
+
+    // Dissonances must pass by step between the downbeats, or step back where neighbor tones are allowed
+    options.retain(|option| {
+        let is_treated = if rules.allow_neighbor_tones { is_valid_passing_tone(prev, *option, next) } else { is_passing_tone(prev, *option, next) };
+        is_consonant(*option, other_note) || is_treated
+    });
+
+    options
+}
+
+/// Whether a dissonance is treated properly by the notes around it: it must be approached by step and then either
+/// carry on by step in the same direction as a passing tone, or step back to the note it came from as a neighbor tone.
+pub fn is_valid_passing_tone(prev: Pitch, dissonant: Pitch, next: Pitch) -> bool {
+    is_passing_tone(prev, dissonant, next) || is_neighbor_tone(prev, dissonant, next)
+}
+
+/// Whether a note steps away from the note before it and straight back again.
+fn is_neighbor_tone(prev: Pitch, cur: Pitch, next: Pitch) -> bool {
+    let motion = DirectedInterval::between(prev, cur).interval().semitones();
+    (1..=Interval::MAJOR_SECOND.semitones()).contains(&motion) && next == prev
+}
+
+/// Whether a note is approached and left by step in the same direction.
+fn is_passing_tone(prev: Pitch, cur: Pitch, next: Pitch) -> bool {
+    let motion = DirectedInterval::between(prev, cur);
+    let next_motion = DirectedInterval::between(cur, next);
+
+    let is_step = |motion: DirectedInterval| (1..=Interval::MAJOR_SECOND.semitones()).contains(&motion.interval().semitones());
+    is_step(motion) && is_step(next_motion) && motion.is_ascending() == next_motion.is_ascending()
+}
+
+/// A depth-first search for lines of counterpoint, which yields each complete line as it's found.
+struct Solutions<'a> {
+    notes: &'a [Pitch],
+    scale: &'a Scale,
+    direction: Direction,
+    rules: &'a RuleSet,
+    rng: Option<&'a mut dyn RngCore>,
+    line: Vec<Pitch>,
+    /// The options left to try for each note of the line, including the one currently being tried
+    options: Vec<Vec<Pitch>>,
+    /// How many more notes may be tried before giving up, if the search is limited
+    steps_left: Option<usize>,
+    stats: SolveStats,
+}
+
+impl<'a> Solutions<'a> {
+    /// Starts a search. If an `rng` is given, the options for each note are tried in a random order rather than the
+    /// order they're generated in.
+    fn new(notes: &'a [Pitch], scale: &'a Scale, direction: Direction, rules: &'a RuleSet, rng: Option<&'a mut dyn RngCore>) -> Self {
+        let mut search = Solutions { notes, scale, direction, rules, rng, line: vec![], options: vec![], steps_left: None, stats: SolveStats::default() };
+        if !notes.is_empty() {
+            let openings = opening_pitches(notes, search.scale, direction, rules);
+            search.push_options(openings);
+        }
+        search
+    }
+
+    /// Whether the search stopped because it ran out of budget with options still left to try, rather than because it
+    /// tried them all.
+    fn is_out_of_budget(&self) -> bool {
+        self.steps_left == Some(0) && !self.options.is_empty()
+    }
+
+    fn push_options(&mut self, mut options: Vec<Pitch>) {
+        // Options are taken from the back
+        if let (Some(rng), Some(&prev), true) = (self.rng.as_mut(), self.line.last(), self.rules.weighted_choice) {
+            let cantus = self.notes[self.line.len()];
+            weighted_shuffle(&mut options, |option| weight_option(prev, *option, cantus), rng);
+            options.reverse();
+        } else if let Some(rng) = self.rng.as_mut() {
+            shuffle(&mut options, rng);
+        } else {
+            options.reverse();
+        }
+        if let (Some(&prev), true) = (self.line.last(), self.rules.prefer_contrary_motion) {
+            // Flip the options round so the ones tried first are at the front while sorting
+            let idx = self.line.len();
+            options.reverse();
+            contrary_first(&mut options, prev, self.notes[idx - 1], self.notes[idx]);
+            options.reverse();
+        }
+        self.options.push(options);
+    }
+}
+
+impl<'a> Iterator for Solutions<'a> {
+    type Item = Vec<Pitch>;
+
+    fn next(&mut self) -> Option<Vec<Pitch>> {
+        loop {
+            let options = self.options.last_mut()?;
+            // Check the budget before taking an option, so that running out of budget leaves the option to be tried
+            // and a search with no options left isn't mistaken for one that ran out of budget
+            if !options.is_empty() {
+                match self.steps_left.as_mut() {
+                    Some(0) => return None,
+                    Some(steps_left) => *steps_left -= 1,
+                    None => {}
+                }
+            }
+            match options.pop() {
+                Some(option) => {
+                    self.line.push(option);
+                    self.stats.nodes += 1;
+                    self.stats.max_depth = self.stats.max_depth.max(self.line.len());
+                    if self.line.len() == self.notes.len() {
+                        let res = self.line.clone();
+                        self.line.pop();
+                        return Some(res);
+                    }
+
+                    let options = candidates(self.notes, &self.line, self.scale, self.direction, &[Interval::UNISON, Interval::OCTAVE], self.rules);
+                    if options.is_empty() {
+                        self.stats.dead_ends += 1;
+                    }
+                    self.push_options(options);
+                }
+                None => {
+                    // We've run out of options here, so backtrack
+                    self.options.pop();
+                    self.line.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Moves the options that make contrary motion against the other line, moving from `other_prev` to `other_note`, to the
+/// front, keeping the order within the contrary and non-contrary options.
+fn contrary_first(options: &mut [Pitch], prev: Pitch, other_prev: Pitch, other_note: Pitch) {
+    options.sort_by_key(|option| motion_type(prev, *option, other_prev, other_note) != Motion::Contrary);
+}
+
+/// Gets the notes that could follow `so_far` against the other line without breaking any rules. The last note must be
+/// one of the `closing` intervals away from the other line.
+fn candidates(notes: &[Pitch], so_far: &[Pitch], scale: &Scale, direction: Direction, closing: &[Interval], rules: &RuleSet) -> Vec<Pitch> {
+    let mut options = consonant_options(notes, so_far.len(), direction, closing, rules);
+    options.retain(|option| obeys_rules(notes, so_far, *option, scale, rules));
+    options
+}
+
+/// Gets the notes that are consonant against the other line at `idx`, or for the last note, the ones that are one of
+/// the `closing` intervals away. Notes before the last are on the chosen side of the other line, and never in unison
+/// with it, unless the rules allow it.
+fn consonant_options(notes: &[Pitch], idx: usize, direction: Direction, closing: &[Interval], rules: &RuleSet) -> Vec<Pitch> {
+    let other_note = notes[idx];
+
+    // If this is the ending, we must choose one of the closing intervals.
+    if idx == notes.len() - 1 {
+        if direction == Direction::Above {
+            closing.iter().map(|interval| other_note + interval).collect()
+        } else {
+            closing.iter().map(|interval| other_note - interval).collect()
+        }
+    } else {
+        // Otherwise, we want a consonant interval.
+        let mut options = interior_consonances(other_note, direction);
+        if rules.allow_voice_crossing {
+            let other_side = if direction == Direction::Above { Direction::Below } else { Direction::Above };
+            options.extend(interior_consonances(other_note, other_side));
+        }
+        if rules.allow_interior_unison {
+            options.push(other_note);
+        }
+        options
+    }
+}
+
+/// Gets the notes a consonant interval away from `other_note` on one side of it, up to a tenth.
+fn interior_consonances(other_note: Pitch, direction: Direction) -> Vec<Pitch> {
+    if direction == Direction::Above {
+        vec![other_note + Interval::PERFECT_FIFTH, other_note + Interval::MINOR_THIRD, other_note + Interval::MAJOR_THIRD, other_note + Interval::MINOR_SIXTH, other_note + Interval::MAJOR_SIXTH, other_note + 12, other_note + 12 + Interval::MINOR_THIRD, other_note + 12 + Interval::MAJOR_THIRD]
+    } else {
+        vec![other_note - Interval::PERFECT_FIFTH, other_note - Interval::MINOR_THIRD, other_note - Interval::MAJOR_THIRD, other_note - Interval::MINOR_SIXTH, other_note - Interval::MAJOR_SIXTH, other_note - 12, other_note - 12 - Interval::MINOR_THIRD, other_note - 12 - Interval::MAJOR_THIRD]
+    }
+}
+
+/// Checks whether `option` can follow `so_far` against the other line, given the notes of the scale, without breaking
+/// any rule. This is `rule_violations` without building the descriptions, for the solver to call on every option.
+fn obeys_rules(notes: &[Pitch], so_far: &[Pitch], option: Pitch, scale: &Scale, rules: &RuleSet) -> bool {
+    let other_note = notes[so_far.len()];
+    let prev_note = so_far[so_far.len() - 1];
+    let other_prev_note = notes[so_far.len() - 1];
+
+    rules.is_in_scale(scale, option)
+        && rules.is_in_range(option)
+        && !rules.is_forbidden_perfect(prev_note, option, other_prev_note, other_note)
+        && (option.semitones_from_middle_c() - other_note.semitones_from_middle_c()).unsigned_abs() <= rules.max_span.semitones() as u16
+        && parallel_imperfect_run(notes, so_far, option) <= rules.max_parallel_imperfects as usize
+        && !(rules.forbid_battuta && is_battuta(prev_note, option, other_prev_note, other_note))
+        && !(rules.forbid_similar_skips && is_similar_skip(prev_note, option, other_prev_note, other_note))
+        && obeys_melodic_rules(so_far, option, so_far.len() == notes.len() - 1, rules)
+}
+
+/// Describes every rule that `option` would break by following `so_far` against the other line, given the notes of the
+/// scale.
+fn rule_violations(notes: &[Pitch], so_far: &[Pitch], option: Pitch, scale: &Scale, rules: &RuleSet) -> Vec<String> {
+    let mut violations = vec![];
+    let measure = so_far.len() + 1;
+    let other_note = notes[so_far.len()];
+    let prev_note = so_far[so_far.len() - 1];
+    let other_prev_note = notes[so_far.len() - 1];
+
+    // We only want notes from the scale.
+    if !rules.is_in_scale(scale, option) {
+        violations.push(format!("{} in measure {} isn't in the scale", option, measure));
+    }
+
+    // Stay within the range.
+    if !rules.is_in_range(option) {
+        violations.push(format!("{} in measure {} is outside of the range {}", option, measure, rules.range.unwrap()));
+    }
+
+    // We don't want direct or parallel fifths or octaves.
+    if rules.is_forbidden_perfect(prev_note, option, other_prev_note, other_note) {
+        let motion = if motion_type(prev_note, option, other_prev_note, other_note) == Motion::Parallel { "parallel" } else { "direct" };
+        let interval = if (option - other_note).simple() == Interval::PERFECT_FIFTH { "fifths" } else { "octaves" };
+        violations.push(format!("{} {} between measures {} and {}", motion, interval, measure - 1, measure));
+    }
+
+    // Don't exceed the span from the other line
+    if (option.semitones_from_middle_c() - other_note.semitones_from_middle_c()).unsigned_abs() > rules.max_span.semitones() as u16 {
+        violations.push(format!("the voices are more than a {} apart in measure {}", rules.max_span, measure));
+    }
+
+    // Don't move in parallel sixths or thirds too many notes at a time.
+    let count = parallel_imperfect_run(notes, so_far, option);
+    if count > rules.max_parallel_imperfects as usize {
+        let interval = if (option - other_note).simple().1 == 3 { "thirds" } else { "sixths" };
+        violations.push(format!("{} parallel {} in a row ending in measure {}", count, interval, measure));
+    }
+
+    // Don't leap into a perfect consonance against a step the other way
+    if rules.forbid_battuta && is_battuta(prev_note, option, other_prev_note, other_note) {
+        let interval = if (option - other_note).simple() == Interval::PERFECT_FIFTH { "a fifth" } else { "an octave" };
+        violations.push(format!("the counterpoint leaps into {} against a step between measures {} and {}", interval, measure - 1, measure));
+    }
+
+    // Don't have both voices skip in the same direction
+    if rules.forbid_similar_skips && is_similar_skip(prev_note, option, other_prev_note, other_note) {
+        violations.push(format!("both voices skip in the same direction between measures {} and {}", measure - 1, measure));
+    }
+
+    violations.extend(melodic_violations(so_far, option, so_far.len() == notes.len() - 1, rules));
+
+    violations
+}
+
+/// Counts the notes in a row, ending with `option`, that sound a third against the other line, or that all sound a sixth,
+/// where each moves to the next in parallel motion. Compound thirds and sixths count too. Gives 0 if `option` doesn't
+/// sound a third or sixth.
+fn parallel_imperfect_run(notes: &[Pitch], so_far: &[Pitch], option: Pitch) -> usize {
+    let kind = |idx: usize, pitch: Pitch| match (pitch - notes[idx]).simple() {
+        Interval::MINOR_THIRD | Interval::MAJOR_THIRD => Some(3),
+        Interval::MINOR_SIXTH | Interval::MAJOR_SIXTH => Some(6),
+        _ => None,
+    };
+    let run_kind = match kind(so_far.len(), option) {
+        Some(run_kind) => run_kind,
+        None => return 0,
+    };
+
+    let mut count = 1;
+    let mut next = option;
+    for (idx, pitch) in so_far.iter().enumerate().rev() {
+        let is_parallel = motion_type(*pitch, next, notes[idx], notes[idx + 1]) == Motion::Parallel;
+        if kind(idx, *pitch) != Some(run_kind) || !is_parallel {
+            break;
+        }
+        count += 1;
+        next = *pitch;
+    }
+    count
+}
+
+/// Checks whether `option` can follow `so_far` in the same line without breaking any rule of melodic motion. This is
+/// `melodic_violations` without building the descriptions.
+fn obeys_melodic_rules(so_far: &[Pitch], option: Pitch, is_last: bool, rules: &RuleSet) -> bool {
+    let prev_note = so_far[so_far.len() - 1];
+    let leap = (option.semitones_from_middle_c() - prev_note.semitones_from_middle_c()).unsigned_abs();
+
+    repeat_count(so_far, option) <= rules.max_consecutive_repeats as usize
+        && leap <= rules.max_leap.semitones() as u16
+        && !(rules.forbid_tritone_leaps && leap == Interval::AUGMENTED_FOURTH.semitones() as u16)
+        && !(rules.forbid_augmented_diminished_motion && is_augmented_or_diminished(prev_note, option))
+        && !(rules.step_to_final && is_last && leap > Interval::MAJOR_SECOND.semitones() as u16)
+        && !(rules.single_climax && is_last && !has_single_climax(so_far, option))
+        && !(rules.recover_leaps && is_unrecovered_leap(so_far, option))
+}
+
+/// Counts the times `option` would sound in a row at the end of `so_far`, including itself.
+fn repeat_count(so_far: &[Pitch], option: Pitch) -> usize {
+    1 + so_far.iter().rev().take_while(|pitch| **pitch == option).count()
+}
+
+/// Checks whether `so_far` followed by `option` reaches its highest note only once.
+fn has_single_climax(so_far: &[Pitch], option: Pitch) -> bool {
+    let mut line = so_far.to_vec();
+    line.push(option);
+    climax(&line).is_some()
+}
+
+/// Checks whether `so_far` ends in a leap larger than a third that `option` doesn't recover from by stepping the other way.
+fn is_unrecovered_leap(so_far: &[Pitch], option: Pitch) -> bool {
+    if so_far.len() < 2 {
+        return false;
+    }
+    let prev_note = so_far[so_far.len() - 1];
+    let leap = DirectedInterval::between(so_far[so_far.len() - 2], prev_note);
+    if leap.interval().semitones() <= Interval::MAJOR_THIRD.semitones() {
+        return false;
+    }
+    let recovery = DirectedInterval::between(prev_note, option);
+    recovery.interval().semitones() > Interval::MAJOR_SECOND.semitones() || recovery.is_ascending() != (-leap).is_ascending()
+}
+
+/// Describes every rule of melodic motion that `option` would break by following `so_far` in the same line, regardless
+/// of what any other line is doing. If `is_last` is set, `option` is the line's final note.
+fn melodic_violations(so_far: &[Pitch], option: Pitch, is_last: bool, rules: &RuleSet) -> Vec<String> {
+    let mut violations = vec![];
+    let measure = so_far.len() + 1;
+    let prev_note = so_far[so_far.len() - 1];
+    let leap = (option.semitones_from_middle_c() - prev_note.semitones_from_middle_c()).unsigned_abs();
+
+    // Don't repeat the same pitch too many times
+    let repeats = repeat_count(so_far, option);
+    if repeats > rules.max_consecutive_repeats as usize {
+        violations.push(format!("{} is repeated {} times in a row ending in measure {}", option, repeats, measure));
+    }
+
+    // Don't leap too far
+    if leap > rules.max_leap.semitones() as u16 {
+        violations.push(format!("the leap between measures {} and {} is larger than a {}", measure - 1, measure, rules.max_leap));
+    }
+
+    // Don't leap by a tritone
+    if rules.forbid_tritone_leaps && leap == Interval::AUGMENTED_FOURTH.semitones() as u16 {
+        violations.push(format!("tritone leap between measures {} and {}", measure - 1, measure));
+    }
+
+    // Don't move by an augmented or diminished interval
+    if rules.forbid_augmented_diminished_motion && is_augmented_or_diminished(prev_note, option) {
+        let interval = DirectedInterval::between(prev_note, option).interval();
+        violations.push(format!("{} between measures {} and {}", interval, measure - 1, measure));
+    }
+
+    // Approach the last note via stepwise motion
+    if rules.step_to_final && is_last && leap > Interval::MAJOR_SECOND.semitones() as u16 {
+        violations.push(format!("the last note in measure {} isn't approached by step", measure));
+    }
+
+    // Build to a single climax
+    if rules.single_climax && is_last && !has_single_climax(so_far, option) {
+        let highest = so_far.iter().copied().chain(Some(option)).max().unwrap();
+        violations.push(format!("the highest note, {}, is reached more than once", highest));
+    }
+
+    // If you leap, you must go the opposite direction by step
+    if rules.recover_leaps && is_unrecovered_leap(so_far, option) {
+        violations.push(format!("the leap into measure {} isn't followed by a step in the opposite direction", measure - 1));
+    }
+
+    violations
+}
+
+/// A rule broken by a line of counterpoint, along with the 1-based measure it was broken in.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuleViolation {
+    pub measure: usize,
+    pub description: String,
+}
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+/// Checks a finished line of counterpoint against the cantus firmus, reporting every rule it breaks. Whether the
+/// counterpoint lies above or below the cantus is taken from the first note that isn't a unison.
+pub fn check_counterpoint(cantus: &[Pitch], counter: &[Pitch], scale: &Scale, rules: &RuleSet) -> Vec<RuleViolation> {
+    let mut violations = vec![];
+    if cantus.len() != counter.len() {
+        let description = format!("the counterpoint has {} notes but the cantus has {}", counter.len(), cantus.len());
+        violations.push(RuleViolation { measure: 1, description });
+    }
+    let len = cantus.len().min(counter.len());
+    if len == 0 {
+        return violations;
+    }
+    let (cantus, counter) = (&cantus[..len], &counter[..len]);
+    let direction = match counter.iter().zip(cantus).find(|(pitch, other)| pitch != other) {
+        Some((pitch, other)) if pitch < other => Direction::Below,
+        _ => Direction::Above,
+    };
+
+    // The first note must be one of the opening intervals.
+    let opening = counter[0] - cantus[0];
+    if !rules.opening_intervals.contains(&opening) {
+        let allowed: Vec<&str> = rules.opening_intervals.iter().map(Interval::abbrev).collect();
+        violations.push(RuleViolation { measure: 1, description: format!("the counterpoint opens on a {}, which isn't one of the allowed openings ({})", opening, allowed.join(", ")) });
+    }
+    if !rules.is_in_scale(scale, counter[0]) {
+        violations.push(RuleViolation { measure: 1, description: format!("{} in measure 1 isn't in the scale", counter[0]) });
+    }
+    if !rules.is_in_range(counter[0]) {
+        violations.push(RuleViolation { measure: 1, description: format!("{} in measure 1 is outside of the range {}", counter[0], rules.range.unwrap()) });
+    }
+
+    for idx in 1..len {
+        let measure = idx + 1;
+        let (option, other_note) = (counter[idx], cantus[idx]);
+        if !consonant_options(cantus, idx, direction, &[Interval::UNISON, Interval::OCTAVE], rules).contains(&option) {
+            let interval = (option - other_note).simple();
+            let is_crossed = if direction == Direction::Above { option < other_note } else { option > other_note };
+            let description = if idx == len - 1 {
+                format!("the counterpoint ends on a {} rather than a unison or octave", interval)
+            } else if !is_consonant(option, other_note) {
+                format!("dissonant {} in measure {}", interval, measure)
+            } else if is_crossed {
+                format!("the voices cross in measure {}", measure)
+            } else if interval == Interval::UNISON {
+                format!("the voices meet in a unison in measure {}", measure)
+            } else {
+                format!("{} against the cantus in measure {} isn't allowed", interval, measure)
+            };
+            violations.push(RuleViolation { measure, description });
+        }
+
+        for description in rule_violations(cantus, &counter[..idx], option, scale, rules) {
+            violations.push(RuleViolation { measure, description });
+        }
+    }
+    violations
+}
+
+/// A cantus firmus that follows the conventions of species counterpoint, ready to write counterpoint against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CantusFirmus(pub Vec<Pitch>);
+
+impl CantusFirmus {
+    /// Creates a cantus firmus from its notes, as long as `validate` finds nothing wrong with them.
+    pub fn new(notes: Vec<Pitch>, scale: &Scale) -> Result<Self, Vec<RuleViolation>> {
+        CantusFirmus::validate(&notes, scale)?;
+        Ok(CantusFirmus(notes))
+    }
+
+    /// Checks that a line is fit to be a cantus firmus: it starts and ends on the root of the scale, stays in the
+    /// scale, moves mostly by step, reaches its highest note only once, and follows the melodic rules of strict
+    /// counterpoint. Reports every rule the line breaks.
+    pub fn validate(notes: &[Pitch], scale: &Scale) -> Result<(), Vec<RuleViolation>> {
+        let (first, last) = match (notes.first(), notes.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return Err(vec![RuleViolation { measure: 1, description: "the cantus firmus has no notes".to_string() }]),
+        };
+
+        let mut violations = vec![];
+        if first.0 != scale.0 {
+            violations.push(RuleViolation { measure: 1, description: format!("the cantus firmus starts on {} rather than {}", first.0, scale.0) });
+        }
+        if last.0 != scale.0 {
+            violations.push(RuleViolation { measure: notes.len(), description: format!("the cantus firmus ends on {} rather than {}", last.0, scale.0) });
+        }
+        for (idx, pitch) in notes.iter().enumerate() {
+            if !scale.contains_pitch(*pitch) {
+                violations.push(RuleViolation { measure: idx + 1, description: format!("{} in measure {} isn't in the scale", pitch, idx + 1) });
+            }
+        }
+
+        // The line should build to a single climax
+        if climax(notes).is_none() {
+            let highest = notes.iter().max().unwrap();
+            let climaxes: Vec<usize> = notes.iter().enumerate().filter(|(_, pitch)| *pitch == highest).map(|(idx, _)| idx + 1).collect();
+            let description = format!("the highest note, {}, is reached {} times rather than once", highest, climaxes.len());
+            violations.push(RuleViolation { measure: climaxes[1], description });
+        }
+
+        // Most of the motion should be stepwise
+        let steps = notes.windows(2).filter(|pair| (pair[1] - pair[0]).semitones() <= Interval::MAJOR_SECOND.semitones()).count();
+        let leaps = notes.len() - 1 - steps;
+        if leaps > steps {
+            violations.push(RuleViolation { measure: 1, description: format!("the cantus firmus leaps {} times but only steps {} times", leaps, steps) });
+        }
+
+        let rules = RuleSet::strict_fux();
+        for idx in 1..notes.len() {
+            for description in melodic_violations(&notes[..idx], notes[idx], idx == notes.len() - 1, &rules) {
+                violations.push(RuleViolation { measure: idx + 1, description });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn multiple_voices() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let voices = counterpoint_multi(&cantus, 2, &scale, &RuleSet::strict_fux(), &mut rand::thread_rng()).expect("no counterpoint found");
+        assert_eq!(voices.len(), 2);
+
+        for voice in &voices {
+            assert_eq!(voice.len(), cantus.len());
+            assert!(voice.iter().all(|pitch| scale.contains_pitch(*pitch)));
+        }
+        for idx in 0..cantus.len() {
+            let sonority: Vec<Pitch> = voices.iter().map(|voice| voice[idx]).collect();
+            assert!(is_consonant_sonority(&sonority));
+            assert!(sonority.iter().all(|pitch| is_consonant(*pitch, cantus[idx])));
+            if idx > 0 {
+                assert!(!is_direct_perfect(voices[0][idx - 1], voices[0][idx], voices[1][idx - 1], voices[1][idx]));
+            }
+        }
+        // The voices open and close on complete sonorities
+        let opening: Vec<Pitch> = voices.iter().map(|voice| voice[0]).collect();
+        let closing: Vec<Pitch> = voices.iter().map(|voice| voice[cantus.len() - 1]).collect();
+        assert!(is_complete_sonority(cantus[0], &opening));
+        assert!(is_complete_sonority(cantus[cantus.len() - 1], &closing));
+
+        // There's nothing to write without a cantus or any voices
+        assert_eq!(counterpoint_multi(&[], 2, &scale, &RuleSet::strict_fux(), &mut StdRng::seed_from_u64(0)), None);
+        assert_eq!(counterpoint_multi(&cantus, 0, &scale, &RuleSet::strict_fux(), &mut StdRng::seed_from_u64(0)), None);
+    }
+
+    #[test]
+    fn second_species() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let line = counterpoint_species(&cantus, &scale, Direction::Below, Species::Second, &RuleSet::strict_fux(), &mut StdRng::seed_from_u64(0)).expect("no counterpoint found");
+        // Two notes against every cantus note but the last
+        assert_eq!(line.len(), cantus.len() * 2 - 1);
+        assert!(line.iter().all(|pitch| scale.contains_pitch(*pitch)));
+
+        for idx in 0..line.len() {
+            let other_note = cantus[idx / 2];
+            // Second species moves in two-two time, with the downbeats strong
+            if is_strong_beat(idx, SECOND_SPECIES_TIME) {
+                // Downbeats are always consonant
+                assert!(is_consonant(line[idx], other_note));
+            } else {
+                // Upbeats may also be passing tones
+                assert!(is_consonant(line[idx], other_note) || is_passing_tone(line[idx - 1], line[idx], line[idx + 1]));
+            }
+        }
+        // The last note is approached by step
+        let last = line.len() - 1;
+        assert!((line[last].semitones_from_middle_c() - line[last - 1].semitones_from_middle_c()).unsigned_abs() <= 2);
+    }
+
+    #[test]
+    fn dissonance_treatment() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        // Passing tones step through in one direction
+        assert!(is_valid_passing_tone(pitch("C4"), pitch("D4"), pitch("E4")));
+        assert!(is_valid_passing_tone(pitch("F4"), pitch("E4"), pitch("D4")));
+        // Neighbor tones step away and back
+        assert!(is_valid_passing_tone(pitch("C4"), pitch("D4"), pitch("C4")));
+        assert!(is_valid_passing_tone(pitch("C4"), pitch("B3"), pitch("C4")));
+        // Leaping into or out of the dissonance isn't allowed
+        assert!(!is_valid_passing_tone(pitch("A3"), pitch("D4"), pitch("E4")));
+        assert!(!is_valid_passing_tone(pitch("C4"), pitch("D4"), pitch("F4")));
+        // Nor is stepping back to a different note
+        assert!(!is_valid_passing_tone(pitch("C4"), pitch("D4"), pitch("C#4")));
+
+        // The solver only writes neighbor tones when they're allowed
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let strict = RuleSet::strict_fux();
+        let neighbors = RuleSet { allow_neighbor_tones: true, ..strict.clone() };
+        assert!(!upbeat_candidates(pitch("A3"), pitch("D3"), pitch("D3"), &scale, Direction::Below, false, &strict).contains(&pitch("E3")));
+        assert!(upbeat_candidates(pitch("A3"), pitch("D3"), pitch("D3"), &scale, Direction::Below, false, &neighbors).contains(&pitch("E3")));
+    }
+
+    #[test]
+    fn first_species() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let line = counterpoint_species(&cantus, &scale, Direction::Below, Species::First, &RuleSet::strict_fux(), &mut StdRng::seed_from_u64(0)).expect("no counterpoint found");
+        assert_eq!(line.len(), cantus.len());
+
+        // There's nothing to write against an empty cantus, in either species
+        for species in &[Species::First, Species::Second] {
+            assert_eq!(counterpoint_species(&[], &scale, Direction::Below, *species, &RuleSet::strict_fux(), &mut StdRng::seed_from_u64(0)), None);
+        }
+    }
+
+    /// Whether every note after the opening is one the solver could have picked under the rules.
+    fn follows_rules(cantus: &[Pitch], line: &[Pitch], scale: &Scale, direction: Direction, rules: &RuleSet) -> bool {
+        (1..line.len()).all(|idx| candidates(cantus, &line[..idx], scale, direction, &[Interval::UNISON, Interval::OCTAVE], rules).contains(&line[idx]))
+    }
+
+    #[test]
+    fn voice_crossing() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        for seed in 0..20 {
+            let line = counterpoint(&cantus, &scale, Direction::Below, &rules, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert!(line.iter().zip(&cantus).all(|(pitch, other)| pitch <= other));
+            let line = counterpoint_species(&cantus, &scale, Direction::Below, Species::Second, &rules, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert!(line.iter().enumerate().all(|(idx, pitch)| *pitch <= cantus[idx / 2]));
+        }
+
+        // Relaxing the rule lets the line cross above the cantus, though it still closes below
+        let cantus = parse_music("D4 A3 C4 B3 A3 D4").unwrap();
+        let crossing = RuleSet { allow_voice_crossing: true, ..rules.clone() };
+        let solutions: Vec<Vec<Pitch>> = counterpoint_all(&cantus, &scale, Direction::Below, &crossing).collect();
+        assert!(solutions.iter().any(|line| line.iter().zip(&cantus).any(|(pitch, other)| pitch > other)));
+        assert!(solutions.iter().all(|line| line[5] <= cantus[5] && check_counterpoint(&cantus, line, &scale, &crossing).is_empty()));
+
+        let crossed = solutions.iter().find(|line| line.iter().zip(&cantus).any(|(pitch, other)| pitch > other)).unwrap();
+        assert!(check_counterpoint(&cantus, crossed, &scale, &rules).iter().any(|violation| violation.description.starts_with("the voices cross")));
+    }
+
+    #[test]
+    fn interior_unisons() {
+        let cantus = parse_music("D4 A3 C4 B3 A3 D4").unwrap();
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        let is_interior_unison = |line: &Vec<Pitch>| (1..cantus.len() - 1).any(|idx| line[idx] == cantus[idx]);
+        for direction in [Direction::Above, Direction::Below] {
+            assert!(counterpoint_all(&cantus, &scale, direction, &rules).all(|line| !is_interior_unison(&line)));
+            let crossing = RuleSet { allow_voice_crossing: true, ..rules.clone() };
+            assert!(counterpoint_all(&cantus, &scale, direction, &crossing).all(|line| !is_interior_unison(&line)));
+        }
+
+        // Relaxing the rule lets the voices meet in the middle
+        let unisons = RuleSet { allow_interior_unison: true, ..rules.clone() };
+        let line = counterpoint_all(&cantus, &scale, Direction::Below, &unisons).find(is_interior_unison).unwrap();
+        assert!(check_counterpoint(&cantus, &line, &scale, &unisons).is_empty());
+        let violations = check_counterpoint(&cantus, &line, &scale, &rules);
+        assert!(violations.iter().any(|violation| violation.description.starts_with("the voices meet in a unison")));
+    }
+
+    #[test]
+    fn climaxes() {
+        let line = |s: &str| parse_music(s).unwrap();
+        assert_eq!(climax(&line("D4 F4 E4 A4 G4 E4 D4")), Some(3));
+        assert_eq!(climax(&line("D4")), Some(0));
+        assert_eq!(climax(&[]), None);
+        // A tied maximum has no single climax, even when spelled differently
+        assert_eq!(climax(&line("D4 A4 G4 A4 D4")), None);
+        assert_eq!(climax(&line("C4 E#4 D4 F4")), None);
+
+        // The solver can be made to write lines with a single climax
+        let cantus = line("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4");
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let rules = RuleSet { single_climax: true, ..RuleSet::strict_fux() };
+        for seed in 0..10 {
+            let line = counterpoint(&cantus, &scale, Direction::Above, &rules, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert!(climax(&line).is_some());
+        }
+    }
+
+    #[test]
+    fn contrary_motion_first() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let contrary_ratio = |rules: &RuleSet| {
+            let mut contrary = 0;
+            for seed in 0..20 {
+                let line = counterpoint(&cantus, &scale, Direction::Below, rules, &mut StdRng::seed_from_u64(seed)).unwrap();
+                assert!(follows_rules(&cantus, &line, &scale, Direction::Below, rules));
+                contrary += (1..line.len()).filter(|&idx| motion_type(line[idx - 1], line[idx], cantus[idx - 1], cantus[idx]) == Motion::Contrary).count();
+            }
+            contrary as f64 / (20 * (cantus.len() - 1)) as f64
+        };
+        let uniform = RuleSet::strict_fux();
+        let contrary = RuleSet { prefer_contrary_motion: true, ..uniform.clone() };
+        assert!(contrary_ratio(&contrary) > contrary_ratio(&uniform));
+
+        // The options keep their order otherwise
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        let mut options = parse_music("C4 A3 E4 B3 F4").unwrap();
+        contrary_first(&mut options, pitch("D4"), pitch("A3"), pitch("B3"));
+        assert_eq!(options, parse_music("C4 A3 B3 E4 F4").unwrap());
+    }
+
+    #[test]
+    fn free_counterpoint() {
+        let cantus = parse_music("D4 E4 F4 E4 D4").unwrap();
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let strict = RuleSet::strict_fux();
+        let free = RuleSet { restrict_to_scale: false, ..strict.clone() };
+        let is_chromatic = |line: &Vec<Pitch>| line.iter().any(|pitch| !scale.contains_pitch(*pitch));
+
+        // Free counterpoint can leave the scale, though it still follows the other rules
+        assert!(!counterpoint_all(&cantus, &scale, Direction::Below, &strict).any(|line| is_chromatic(&line)));
+        let line = counterpoint_all(&cantus, &scale, Direction::Below, &free).find(is_chromatic).unwrap();
+        assert!(follows_rules(&cantus, &line, &scale, Direction::Below, &free));
+        assert!(check_counterpoint(&cantus, &line, &scale, &free).is_empty());
+        assert!(!check_counterpoint(&cantus, &line, &scale, &strict).is_empty());
+
+        // A passing tone on C♯3 from B2 up to D3 is only allowed in free counterpoint
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        assert!(!upbeat_candidates(pitch("A3"), pitch("B2"), pitch("D3"), &scale, Direction::Below, false, &strict).contains(&pitch("C#3")));
+        assert!(upbeat_candidates(pitch("A3"), pitch("B2"), pitch("D3"), &scale, Direction::Below, false, &free).contains(&pitch("C#3")));
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        assert!(counterpoint_species(&cantus, &scale, Direction::Below, Species::Second, &free, &mut StdRng::seed_from_u64(1)).is_some());
+    }
+
+    #[test]
+    fn rule_sets() {
+        let cantus = parse_music("D4 E4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        assert_eq!(RuleSet::default(), RuleSet::strict_fux());
+
+        // Parallel fifths between the first two notes
+        let line = parse_music("G3 A3 D4 C4 D4").unwrap();
+        assert!(!follows_rules(&cantus, &line, &scale, Direction::Below, &RuleSet::strict_fux()));
+        let relaxed = RuleSet { forbid_parallel_fifths: false, ..RuleSet::strict_fux() };
+        assert!(follows_rules(&cantus, &line, &scale, Direction::Below, &relaxed));
+
+        // A leap of a fourth followed by a skip in the opposite direction
+        let line = parse_music("G3 C4 A3 C4 D4").unwrap();
+        assert!(!follows_rules(&cantus, &line, &scale, Direction::Below, &RuleSet::strict_fux()));
+        let relaxed = RuleSet { recover_leaps: false, ..RuleSet::strict_fux() };
+        assert!(follows_rules(&cantus, &line, &scale, Direction::Below, &relaxed));
+
+        // The relaxed rules still find counterpoint
+        assert!(counterpoint(&cantus, &scale, Direction::Below, &relaxed, &mut rand::thread_rng()).is_some());
+    }
+
+    #[test]
+    fn all_solutions() {
+        let cantus = parse_music("D4 E4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        let solutions: Vec<Vec<Pitch>> = counterpoint_all(&cantus, &scale, Direction::Below, &rules).collect();
+        assert!(!solutions.is_empty());
+
+        // Every solution is valid and distinct
+        for (idx, line) in solutions.iter().enumerate() {
+            assert_eq!(line.len(), cantus.len());
+            assert!(follows_rules(&cantus, line, &scale, Direction::Below, &rules));
+            assert!(!solutions[idx + 1..].contains(line));
+        }
+        // The order is deterministic
+        assert_eq!(counterpoint_all(&cantus, &scale, Direction::Below, &rules).collect::<Vec<_>>(), solutions);
+        // The randomized search finds one of them
+        let line = counterpoint(&cantus, &scale, Direction::Below, &rules, &mut rand::thread_rng()).unwrap();
+        assert!(solutions.contains(&line));
+    }
+
+    #[test]
+    fn seeded() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        let line = counterpoint(&cantus, &scale, Direction::Below, &rules, &mut StdRng::seed_from_u64(42));
+        assert!(line.is_some());
+        // The same seed always gives the same line
+        for _ in 0..5 {
+            assert_eq!(counterpoint(&cantus, &scale, Direction::Below, &rules, &mut StdRng::seed_from_u64(42)), line);
+        }
+        let species = Species::Second;
+        let line = counterpoint_species(&cantus, &scale, Direction::Below, species, &rules, &mut StdRng::seed_from_u64(7));
+        assert_eq!(counterpoint_species(&cantus, &scale, Direction::Below, species, &rules, &mut StdRng::seed_from_u64(7)), line);
+    }
+
+    #[test]
+    fn fixed_seed_lines() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        let line = |s: &str| parse_music(s).unwrap();
+        // Filtering the options shouldn't change which lines a given seed finds
+        let below = counterpoint(&cantus, &scale, Direction::Below, &rules, &mut StdRng::seed_from_u64(42));
+        assert_eq!(below, Some(line("G3 F3 C3 D3 E3 F3 F3 G3 A3 C3 D3")));
+        let above = counterpoint(&cantus, &scale, Direction::Above, &rules, &mut StdRng::seed_from_u64(42));
+        assert_eq!(above, Some(line("A4 A4 B4 D5 D5 F5 C5 D5 D5 C5 D5")));
+        let second = counterpoint_species(&cantus, &scale, Direction::Below, Species::Second, &rules, &mut StdRng::seed_from_u64(7));
+        assert_eq!(second, Some(line("G3 D4 D3 A3 E3 C3 G3 D4 G3 G4 A3 D3 F3 F4 G3 E4 A3 F3 C3 E3 D3")));
+        let multi = counterpoint_multi(&cantus, 2, &scale, &rules, &mut StdRng::seed_from_u64(3));
+        assert_eq!(multi, Some(vec![line("A4 A4 G4 A4 B4 C5 C5 D5 D5 G4 A4"), line("F5 C5 C5 F5 E5 F5 F5 G5 D5 E5 F5")]));
+    }
+
+    #[test]
+    fn fourths_in_context() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        // A fourth above the bass is dissonant, but a fourth between upper voices is consonant
+        assert!(!interval_is_consonant_in_context(pitch("D3"), pitch("G3"), true));
+        assert!(interval_is_consonant_in_context(pitch("D4"), pitch("G4"), false));
+        assert!(interval_is_consonant_in_context(pitch("D4"), pitch("G5"), false));
+        // The tritone and the other dissonances stay dissonant between upper voices
+        assert!(!interval_is_consonant_in_context(pitch("F4"), pitch("B4"), false));
+        assert!(!interval_is_consonant_in_context(pitch("B4"), pitch("F5"), false));
+        assert!(!interval_is_consonant_in_context(pitch("D4"), pitch("E4"), false));
+        // Other consonances are consonant either way
+        assert!(interval_is_consonant_in_context(pitch("D3"), pitch("A3"), true));
+        assert!(interval_is_consonant_in_context(pitch("D3"), pitch("F3"), false));
+        assert!(is_consonant_sonority(&[pitch("A4"), pitch("D5")]));
+    }
+
+    #[test]
+    fn uniform_shuffle() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = [[0; 4]; 4];
+        let trials = 4000;
+        for _ in 0..trials {
+            let mut items = [0, 1, 2, 3];
+            shuffle(&mut items, &mut rng);
+            for (position, item) in items.iter().enumerate() {
+                counts[*item][position] += 1;
+            }
+        }
+        // Each element reaches each position, including its own, about a quarter of the time
+        for row in &counts {
+            for count in row {
+                assert!((800..1200).contains(count), "{:?}", counts);
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_choice() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        // Steps against imperfect consonances are favored most
+        assert!(weight_option(pitch("D4"), pitch("E4"), pitch("C5")) > weight_option(pitch("D4"), pitch("E4"), pitch("E5")));
+        assert!(weight_option(pitch("D4"), pitch("E4"), pitch("E5")) > weight_option(pitch("D4"), pitch("A4"), pitch("E5")));
+        assert!(weight_option(pitch("D4"), pitch("A4"), pitch("E5")) > weight_option(pitch("D4"), pitch("D4"), pitch("A4")));
+
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let uniform = RuleSet::strict_fux();
+        let weighted = RuleSet { weighted_choice: true, ..uniform.clone() };
+        let steps = |rules: &RuleSet| -> usize {
+            (0..100)
+                .map(|seed| {
+                    let line = counterpoint(&cantus, &scale, Direction::Below, rules, &mut StdRng::seed_from_u64(seed)).unwrap();
+                    assert!(follows_rules(&cantus, &line, &scale, Direction::Below, rules));
+                    line.windows(2).filter(|pair| (pair[1].semitones_from_middle_c() - pair[0].semitones_from_middle_c()).abs() <= 2).count()
+                })
+                .sum()
+        };
+        assert!(steps(&weighted) > steps(&uniform));
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut items = vec![1, 2, 3, 4];
+        weighted_shuffle(&mut items, |item| *item as f64, &mut rng);
+        items.sort();
+        assert_eq!(items, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn hidden_octaves() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        // Similar motion into an octave, a twelfth, and a fifteenth
+        assert!(is_direct_perfect(pitch("A3"), pitch("D4"), pitch("F4"), pitch("D5")));
+        assert!(is_direct_perfect(pitch("A2"), pitch("D3"), pitch("F4"), pitch("A4")));
+        assert!(is_direct_perfect(pitch("A2"), pitch("D3"), pitch("F4"), pitch("D5")));
+        // Contrary and oblique motion are fine
+        assert!(!is_direct_perfect(pitch("F4"), pitch("D4"), pitch("A4"), pitch("D5")));
+        assert!(!is_direct_perfect(pitch("D4"), pitch("D4"), pitch("F4"), pitch("A4")));
+
+        // The cantus leaps up a fifth, and the counterpoint would follow it into an octave
+        let cantus = parse_music("D4 A4 G4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let so_far = [pitch("D3")];
+        let rules = RuleSet { forbid_similar_skips: false, ..RuleSet::strict_fux() };
+        let closing = [Interval::UNISON, Interval::OCTAVE];
+        assert!(!candidates(&cantus, &so_far, &scale, Direction::Below, &closing, &rules).contains(&pitch("A3")));
+        let rules = RuleSet { forbid_parallel_octaves: false, ..rules.clone() };
+        assert!(candidates(&cantus, &so_far, &scale, Direction::Below, &closing, &rules).contains(&pitch("A3")));
+    }
+
+    #[test]
+    fn check_lines() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+
+        // The solver's own output breaks no rules
+        for _ in 0..5 {
+            let line = counterpoint(&cantus, &scale, Direction::Above, &rules, &mut rand::thread_rng()).unwrap();
+            assert_eq!(check_counterpoint(&cantus, &line, &scale, &rules), vec![]);
+            let line = counterpoint(&cantus, &scale, Direction::Below, &rules, &mut rand::thread_rng()).unwrap();
+            assert_eq!(check_counterpoint(&cantus, &line, &scale, &rules), vec![]);
+        }
+
+        // Parallel fifths between the third and fourth measures
+        let cantus = parse_music("D4 E4 F4 E4 D4").unwrap();
+        let line = parse_music("A4 G4 C5 B4 D5").unwrap();
+        let violations = check_counterpoint(&cantus, &line, &scale, &rules);
+        assert!(violations.contains(&RuleViolation { measure: 4, description: "parallel fifths between measures 3 and 4".to_string() }));
+        assert!(violations.iter().all(|violation| violation.measure != 2));
+
+        // A dissonance, a tritone leap, and a bad ending
+        let line = parse_music("D5 B4 F5 A4 F4").unwrap();
+        let violations: Vec<String> = check_counterpoint(&cantus, &line, &scale, &rules).iter().map(|violation| violation.to_string()).collect();
+        assert!(violations.contains(&"dissonant perfect fourth in measure 4".to_string()));
+        assert!(violations.contains(&"tritone leap between measures 2 and 3".to_string()));
+        assert!(violations.contains(&"the counterpoint ends on a minor third rather than a unison or octave".to_string()));
+
+        // Relaxed rules report less
+        let relaxed = RuleSet { forbid_tritone_leaps: false, ..rules.clone() };
+        assert!(!check_counterpoint(&cantus, &line, &scale, &relaxed).iter().any(|violation| violation.description.starts_with("tritone")));
+        // The lines must be the same length
+        assert_eq!(check_counterpoint(&cantus, &line[..4], &scale, &rules)[0].description, "the counterpoint has 4 notes but the cantus has 5");
+    }
+
+    #[test]
+    fn cantus_firmus() {
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        assert_eq!(CantusFirmus::validate(&cantus, &scale), Ok(()));
+        assert_eq!(CantusFirmus::new(cantus.clone(), &scale), Ok(CantusFirmus(cantus)));
+
+        // Reaching A4 twice makes two climaxes
+        let cantus = parse_music("D4 F4 E4 A4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let violations = CantusFirmus::validate(&cantus, &scale).unwrap_err();
+        assert_eq!(violations, vec![RuleViolation { measure: 7, description: "the highest note, A4, is reached 2 times rather than once".to_string() }]);
+
+        // Starting and ending off the root, and leaping into the last note
+        let violations: Vec<String> = CantusFirmus::validate(&parse_music("F4 E4 D4 E4 A4").unwrap(), &scale)
+            .unwrap_err()
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(violations, vec![
+            "the cantus firmus starts on F rather than D",
+            "the cantus firmus ends on A rather than D",
+            "the last note in measure 5 isn't approached by step",
+        ]);
+
+        // A line that mostly leaps isn't a cantus
+        let violations = CantusFirmus::validate(&parse_music("D4 F4 A4 F4 E4 C4 D4").unwrap(), &scale).unwrap_err();
+        assert!(violations.iter().any(|violation| violation.description.starts_with("the cantus firmus leaps")));
+        assert!(CantusFirmus::validate(&[], &scale).is_err());
+    }
+
+    #[test]
+    fn leap_and_span_limits() {
+        let cantus = parse_music("D4 A4 G4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let closing = [Interval::UNISON, Interval::OCTAVE];
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+
+        let strict = RuleSet::strict_fux();
+        assert_eq!(strict.max_leap, Interval::OCTAVE);
+        assert_eq!(strict.max_span, Interval(IntervalQuality::Major, 10));
+
+        // Renaissance style only allows leaps up to a minor sixth, so A4 can't leap down an octave
+        let renaissance = RuleSet { max_leap: Interval::MINOR_SIXTH, ..strict.clone() };
+        assert!(candidates(&cantus, &[pitch("A4")], &scale, Direction::Below, &closing, &strict).contains(&pitch("A3")));
+        assert!(!candidates(&cantus, &[pitch("A4")], &scale, Direction::Below, &closing, &renaissance).contains(&pitch("A3")));
+        assert!(check_counterpoint(&cantus, &parse_music("A4 A3 G3").unwrap(), &scale, &renaissance)
+            .iter()
+            .any(|violation| violation.description == "the leap between measures 1 and 2 is larger than a minor sixth"));
+
+        // A narrower span keeps the voices within an octave, ruling out a tenth below
+        let narrow = RuleSet { max_span: Interval::OCTAVE, ..strict.clone() };
+        assert!(candidates(&cantus, &[pitch("D4")], &scale, Direction::Below, &closing, &strict).contains(&pitch("F3")));
+        assert!(!candidates(&cantus, &[pitch("D4")], &scale, Direction::Below, &closing, &narrow).contains(&pitch("F3")));
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        assert!(counterpoint(&cantus, &scale, Direction::Below, &narrow, &mut rand::thread_rng()).is_some());
+    }
+
+    #[test]
+    fn either_direction() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        for _ in 0..5 {
+            let (direction, line) = counterpoint_either(&cantus, &scale, &rules, &mut rand::thread_rng()).expect("no counterpoint found");
+            // The line lies on the side it says it does
+            assert!(line.iter().zip(&cantus).all(|(pitch, other)| if direction == Direction::Above { pitch >= other } else { pitch <= other }));
+            assert_eq!(check_counterpoint(&cantus, &line, &scale, &rules), vec![]);
+        }
+    }
+
+    #[test]
+    fn harmonized() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        let (above, below) = harmonize(&cantus, &scale, &rules, &mut rand::thread_rng());
+        let (above, below) = (above.expect("no counterpoint above"), below.expect("no counterpoint below"));
+        assert!(above.iter().zip(&cantus).all(|(pitch, other)| pitch >= other));
+        assert!(below.iter().zip(&cantus).all(|(pitch, other)| pitch <= other));
+        assert_eq!(check_counterpoint(&cantus, &above, &scale, &rules), vec![]);
+        assert_eq!(check_counterpoint(&cantus, &below, &scale, &rules), vec![]);
+    }
+
+    #[test]
+    fn search_budget() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        // A line of eleven notes can't be written in fewer than eleven steps
+        assert_eq!(counterpoint_with_budget(&cantus, &scale, Direction::Below, &rules, 10, &mut rand::thread_rng()), Err(SearchError::OutOfBudget));
+        let line = counterpoint_with_budget(&cantus, &scale, Direction::Below, &rules, 1_000_000, &mut rand::thread_rng()).unwrap();
+        assert_eq!(check_counterpoint(&cantus, &line, &scale, &rules), vec![]);
+        // C# isn't in D Dorian, so there's nothing to open on
+        let cantus = parse_music("C#4 D4").unwrap();
+        assert_eq!(counterpoint_with_budget(&cantus, &scale, Direction::Below, &rules, 10, &mut rand::thread_rng()), Err(SearchError::NoSolution));
+
+        // A budget of exactly the steps it takes to try everything is enough to know there's no solution
+        let cantus = parse_music("D4 F4 E4 D4").unwrap();
+        let stuck = RuleSet { max_leap: Interval::UNISON, max_consecutive_repeats: 1, ..rules.clone() };
+        let (line, stats) = counterpoint_with_stats(&cantus, &scale, Direction::Below, &stuck, &mut StdRng::seed_from_u64(0));
+        assert_eq!(line, None);
+        assert!(stats.nodes > 0);
+        assert_eq!(counterpoint_with_budget(&cantus, &scale, Direction::Below, &stuck, stats.nodes, &mut StdRng::seed_from_u64(0)), Err(SearchError::NoSolution));
+        assert_eq!(counterpoint_with_budget(&cantus, &scale, Direction::Below, &stuck, stats.nodes - 1, &mut StdRng::seed_from_u64(0)), Err(SearchError::OutOfBudget));
+        assert_eq!(counterpoint_best(&cantus, &scale, Direction::Below, &stuck, stats.nodes, &mut StdRng::seed_from_u64(0)), Err(SearchError::NoSolution));
+    }
+
+    #[test]
+    fn ranges() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet { range: Some(Range::BASS), ..RuleSet::strict_fux() };
+        for _ in 0..10 {
+            let line = counterpoint(&cantus, &scale, Direction::Below, &rules, &mut rand::thread_rng()).expect("no counterpoint found");
+            assert!(line.iter().all(|pitch| Range::BASS.contains(*pitch)));
+            assert_eq!(check_counterpoint(&cantus, &line, &scale, &rules), vec![]);
+        }
+        let alto = RuleSet { range: Some(Range::ALTO), ..rules.clone() };
+        for line in counterpoint_all(&cantus, &scale, Direction::Above, &alto).take(20) {
+            assert!(line.iter().all(|pitch| Range::ALTO.contains(*pitch)));
+        }
+
+        // A bass line can't go above middle C
+        let line = parse_music("D4 D4 C4 B3 B3 A3 A3 C4 A3 C3 D3").unwrap();
+        let violations = check_counterpoint(&cantus, &line, &scale, &rules);
+        assert_eq!(violations[0], RuleViolation { measure: 1, description: "D4 in measure 1 is outside of the range E2 to C4".to_string() });
+        assert_eq!(violations.iter().filter(|violation| violation.description.contains("outside of the range")).count(), 2);
+    }
+
+    #[test]
+    fn repeated_pitches() {
+        let rules = RuleSet::strict_fux();
+        let repeats = |line: &str, rules: &RuleSet| {
+            let pitches = parse_music(line).unwrap();
+            let (last, so_far) = pitches.split_last().unwrap();
+            melodic_violations(so_far, *last, false, rules).into_iter().filter(|violation| violation.contains("repeated")).collect::<Vec<String>>()
+        };
+        // A third A3 in a row is one too many under the default
+        assert!(repeats("A3 A3", &rules).is_empty());
+        assert_eq!(repeats("A3 A3 A3", &rules), vec!["A3 is repeated 3 times in a row ending in measure 3"]);
+        assert!(repeats("A3 A3 A3", &RuleSet { max_consecutive_repeats: 3, ..rules.clone() }).is_empty());
+        // The same note in another octave isn't a repeat
+        assert!(repeats("A3 A3 A4", &rules).is_empty());
+        // B♯3 and C4 sound the same, so they are
+        assert_eq!(repeats("C4 B#3 C4", &rules), vec!["C4 is repeated 3 times in a row ending in measure 3"]);
+        assert_eq!(repeats("B#3 C4", &RuleSet { max_consecutive_repeats: 1, ..rules.clone() }), vec!["C4 is repeated 2 times in a row ending in measure 2"]);
+
+        // Lines that hold a pitch for three notes are rejected when checked
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let line = parse_music("D3 D3 D3 B2 B2 A2 A2 C3 A2 C3 D3").unwrap();
+        assert!(check_counterpoint(&cantus, &line, &scale, &rules).iter().any(|violation| violation.description == "D3 is repeated 3 times in a row ending in measure 3"));
+    }
+
+    #[test]
+    fn parallel_imperfects() {
+        let scale = Scale(Note::C, ScaleType::Ionian);
+        let rules = RuleSet::strict_fux();
+        let cantus = parse_music("E4 F4 G4 A4 B4 C5").unwrap();
+        let run = |line: &str| {
+            let pitches = parse_music(line).unwrap();
+            let (last, so_far) = pitches.split_last().unwrap();
+            let violations = rule_violations(&cantus, so_far, *last, &scale, &rules);
+            (parallel_imperfect_run(&cantus, so_far, *last), violations.into_iter().filter(|violation| violation.contains("parallel")).collect::<Vec<_>>())
+        };
+        // Exactly three parallel thirds are fine, but a fourth is one too many
+        assert_eq!(run("C4 D4 E4"), (3, vec![]));
+        assert_eq!(run("C4 D4 E4 F4"), (4, vec!["4 parallel thirds in a row ending in measure 4".to_string()]));
+        // Thirds and tenths both count, but a sixth breaks the run
+        assert_eq!(run("C3 D3 E4 F4").0, 4);
+        assert_eq!(run("C4 A3 E4 F4").0, 2);
+        // Sixths are counted separately
+        assert_eq!(run("G3 A3 B3 C4").0, 4);
+        assert_eq!(run("G3 A3 B3 C4").1, vec!["4 parallel sixths in a row ending in measure 4".to_string()]);
+        // A note that isn't a third or sixth starts no run
+        assert_eq!(run("C4 D4 E4 F4 G4 C4").0, 0);
+        let none = RuleSet { max_parallel_imperfects: 0, ..rules.clone() };
+        assert!(rule_violations(&cantus, &parse_music("C4").unwrap(), "B3".parse().unwrap(), &scale, &none).iter().all(|violation| !violation.contains("parallel")));
+
+        // Thirds on either side of a held cantus note aren't parallel
+        let held = parse_music("E4 E4").unwrap();
+        assert_eq!(parallel_imperfect_run(&held, &parse_music("C4").unwrap(), "G4".parse().unwrap()), 1);
+    }
+
+    #[test]
+    fn predicates_match_descriptions() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let strict = RuleSet::strict_fux();
+        let everything = RuleSet { single_climax: true, forbid_battuta: true, max_parallel_imperfects: 1, range: Some(Range("C3".parse().unwrap(), "C5".parse().unwrap())), ..strict.clone() };
+        for rules in &[strict.clone(), everything] {
+            for seed in 0..5 {
+                let line = counterpoint(&cantus, &scale, Direction::Below, &strict, &mut StdRng::seed_from_u64(seed)).unwrap();
+                for idx in 1..line.len() {
+                    let is_last = idx == line.len() - 1;
+                    for option in (36..84).filter_map(Pitch::from_midi) {
+                        let so_far = &line[..idx];
+                        assert_eq!(obeys_rules(&cantus, so_far, option, &scale, rules), rule_violations(&cantus, so_far, option, &scale, rules).is_empty());
+                        assert_eq!(obeys_melodic_rules(so_far, option, is_last, rules), melodic_violations(so_far, option, is_last, rules).is_empty());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn battuta() {
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let strict = RuleSet::strict_fux();
+        let renaissance = RuleSet { forbid_battuta: true, ..strict.clone() };
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        let leaps = |cantus: &str, line: &str, rules: &RuleSet| {
+            let (cantus, line) = (parse_music(cantus).unwrap(), parse_music(line).unwrap());
+            rule_violations(&cantus, &line[..1], line[1], &scale, rules).into_iter().filter(|violation| violation.contains("leaps into")).collect::<Vec<_>>()
+        };
+        // The cantus steps down from E4 to D4 while the counterpoint leaps up from A3 into the octave
+        assert_eq!(leaps("E4 D4", "A3 D4", &renaissance), vec!["the counterpoint leaps into an octave against a step between measures 1 and 2"]);
+        assert!(leaps("E4 D4", "A3 D4", &strict).is_empty());
+        assert!(is_battuta(pitch("A3"), pitch("D3"), pitch("C4"), pitch("D4")));
+        assert_eq!(leaps("E4 D4", "C3 G3", &renaissance), vec!["the counterpoint leaps into a fifth against a step between measures 1 and 2"]);
+        // Stepping into the octave, or leaping while the cantus leaps too, is fine
+        assert!(leaps("E4 D4", "C4 D4", &renaissance).is_empty());
+        assert!(leaps("G4 D4", "A3 D4", &renaissance).is_empty());
+
+        // The solver never writes one when it's forbidden
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        for seed in 0..10 {
+            let line = counterpoint(&cantus, &scale, Direction::Below, &renaissance, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert!((1..line.len()).all(|idx| !is_battuta(line[idx - 1], line[idx], cantus[idx - 1], cantus[idx])));
+        }
+    }
+
+    #[test]
+    fn opening_intervals() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note::D, ScaleType::Dorian);
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        let rules = RuleSet::strict_fux();
+        assert_eq!(opening_pitches(&cantus, &scale, Direction::Above, &rules), [pitch("D4"), pitch("A4"), pitch("D5")]);
+
+        // With only the octave allowed, every line opens an octave away from the cantus
+        let octave = RuleSet { opening_intervals: vec![Interval::OCTAVE], ..rules.clone() };
+        for seed in 0..5 {
+            let below = counterpoint(&cantus, &scale, Direction::Below, &octave, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert_eq!(below[0], pitch("D3"));
+            let above = counterpoint(&cantus, &scale, Direction::Above, &octave, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert_eq!(above[0], pitch("D5"));
+            assert!(check_counterpoint(&cantus, &above, &scale, &octave).is_empty());
+        }
+
+        // Opening on a unison is reported when it isn't allowed
+        let line = parse_music("D4 D4 C4 B3 B3 D4 C4 B3 A3 C#4 D4").unwrap();
+        assert!(check_counterpoint(&cantus, &line, &scale, &rules).iter().all(|violation| !violation.description.starts_with("the counterpoint opens")));
+        let violations = check_counterpoint(&cantus, &line, &scale, &octave);
+        assert_eq!(violations[0].description, "the counterpoint opens on a perfect unison, which isn't one of the allowed openings (P8)");
+    }
+
+    #[test]
+    fn augmented_and_diminished_motion() {
+        let rules = RuleSet::strict_fux();
+        let violations = |line: &str, rules: &RuleSet| {
+            let pitches = parse_music(line).unwrap();
+            let (last, so_far) = pitches.split_last().unwrap();
+            melodic_violations(so_far, *last, false, rules)
+        };
+        // F to G♯ in A harmonic minor is an augmented second, even though it's only three semitones
+        assert_eq!(violations("F4 G#4", &rules), vec!["augmented second between measures 1 and 2"]);
+        assert_eq!(violations("G#4 F4", &rules), vec!["augmented second between measures 1 and 2"]);
+        assert_eq!(violations("F4 Ab4", &rules), Vec::<String>::new());
+        assert_eq!(violations("G#4 C5", &rules), vec!["diminished fourth between measures 1 and 2"]);
+        assert!(violations("F4 G#4", &RuleSet { forbid_augmented_diminished_motion: false, ..rules.clone() }).is_empty());
+
+        // The solver never moves between the sixth and seventh degrees of harmonic minor
+        let cantus = parse_music("A3 C4 B3 D4 C4 E4 D4 C4 B3 A3").unwrap();
+        let scale = Scale(Note(PitchBase::A, PitchModifier::Natural), ScaleType::HarmonicMinor);
+        let mut found = 0;
+        for line in counterpoint_all(&cantus, &scale, Direction::Above, &rules).take(200) {
+            for pair in line.windows(2) {
+                let quality = DirectedInterval::between(pair[0], pair[1]).interval().0;
+                assert!(!matches!(quality, IntervalQuality::Augmented | IntervalQuality::Diminished), "{:?}", line);
+            }
+            found += 1;
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn search_stats() {
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        let (line, stats) = counterpoint_with_stats(&cantus, &scale, Direction::Below, &rules, &mut rand::thread_rng());
+        assert!(line.is_some());
+        assert_eq!(stats.max_depth, cantus.len());
+        assert!(stats.nodes >= cantus.len());
+
+        // Without being able to move or repeat a note, each of the three openings is a dead end
+        let stuck = RuleSet { max_leap: Interval::UNISON, max_consecutive_repeats: 1, ..rules.clone() };
+        let (line, stats) = counterpoint_with_stats(&cantus, &scale, Direction::Below, &stuck, &mut rand::thread_rng());
+        assert_eq!(line, None);
+        assert_eq!(stats, SolveStats { nodes: 3, dead_ends: 3, max_depth: 1 });
+
+        // With nothing to open on, nothing is tried at all
+        let (line, stats) = counterpoint_with_stats(&parse_music("C#4 D4").unwrap(), &scale, Direction::Below, &rules, &mut rand::thread_rng());
+        assert_eq!(line, None);
+        assert_eq!(stats, SolveStats::default());
+    }
+
+    #[test]
+    fn smoothest_line() {
+        // Steps cost nothing, but leaps, repeats, and leaping on in the same direction do
+        assert_eq!(score_line(&parse_music("D4 E4 F4 E4 D4").unwrap()), 0);
+        assert_eq!(score_line(&parse_music("D4 D4 E4").unwrap()), -3);
+        assert_eq!(score_line(&parse_music("D4 A4 G4").unwrap()), -5);
+        assert_eq!(score_line(&parse_music("D4 F4 A4").unwrap()), -8);
+        assert_eq!(score_line(&parse_music("D4 F4 D4").unwrap()), -2);
+
+        let cantus = parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap();
+        let scale = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian);
+        let rules = RuleSet::strict_fux();
+        let best = counterpoint_best(&cantus, &scale, Direction::Below, &rules, 100_000, &mut rand::thread_rng()).unwrap();
+        assert_eq!(check_counterpoint(&cantus, &best, &scale, &rules), vec![]);
+        // Searching the whole space finds nothing smoother
+        let smoothest = counterpoint_all(&cantus, &scale, Direction::Below, &rules).map(|line| score_line(&line)).max().unwrap();
+        let best = counterpoint_best(&cantus, &scale, Direction::Below, &rules, usize::MAX, &mut rand::thread_rng()).unwrap();
+        assert_eq!(score_line(&best), smoothest);
+    }
+}
@@ -1,46 +1,210 @@
-use lazy_static::lazy_static;
-use std::collections::HashMap;
-use std::fmt;
-use std::ops;
-use strum_macros::Display;
+//! Music theory types: notes, pitches, intervals, scales, and chords, along with a counterpoint solver and exporters.
+//!
+//! The core types only need `alloc`, so the crate can be built without the default `std` feature for use on embedded
+//! targets. The counterpoint solver, tunings, frequencies, and MIDI and WAV export need `std`.
 
-#[derive(Clone, Copy, Debug, Display)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::{String, ToString}, vec, vec::Vec};
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops;
+
+pub mod abc;
+#[cfg(feature = "std")]
+mod counterpoint;
+pub mod lilypond;
+#[cfg(feature = "std")]
+pub mod midi;
+pub mod musicxml;
+mod parse;
+mod rhythm;
+mod score;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod staff;
+#[cfg(feature = "std")]
+mod tuning;
+#[cfg(feature = "std")]
+pub mod wav;
+
+#[cfg(feature = "std")]
+pub use counterpoint::{
+    check_counterpoint, climax, counterpoint, counterpoint_all, counterpoint_best, counterpoint_either,
+    counterpoint_multi, counterpoint_species, counterpoint_with_budget, counterpoint_with_stats, harmonize,
+    interval_is_consonant_in_context, is_valid_passing_tone, score_line, weight_option, CantusFirmus, Direction,
+    RuleSet, RuleViolation, SearchError, SolveStats, Species,
+};
+pub use parse::{parse_events, parse_music, parse_solfege, ParseError, ParseErrorKind};
+pub use rhythm::{Duration, Event, NoteValue, Tuplet};
+pub use score::{is_strong_beat, Score, Voice};
+#[cfg(feature = "std")]
+pub use tuning::{EqualTemperament, JustIntonation, Tuning};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PitchBase {
-    #[strum(serialize="C")]
     C,
-    #[strum(serialize="D")]
     D,
-    #[strum(serialize="E")]
     E,
-    #[strum(serialize="F")]
     F,
-    #[strum(serialize="G")]
     G,
-    #[strum(serialize="A")]
     A,
-    #[strum(serialize="B")]
     B,
 }
 
-#[derive(Clone, Copy, Debug, Display)]
+/// Every letter name, from C up to B.
+const PITCH_BASES: [PitchBase; 7] = [PitchBase::C, PitchBase::D, PitchBase::E, PitchBase::F, PitchBase::G, PitchBase::A, PitchBase::B];
+
+impl PitchBase {
+    /// Gets every letter name in order from C up to B.
+    pub fn all() -> &'static [PitchBase; 7] {
+        &PITCH_BASES
+    }
+
+    /// Gets the number of letter names above C, where C is 0 and B is 6.
+    fn steps_from_c(&self) -> u8 {
+        match *self {
+            PitchBase::C => 0,
+            PitchBase::D => 1,
+            PitchBase::E => 2,
+            PitchBase::F => 3,
+            PitchBase::G => 4,
+            PitchBase::A => 5,
+            PitchBase::B => 6,
+        }
+    }
+
+    fn from_steps_from_c(steps: u8) -> Self {
+        match steps % 7 {
+            0 => PitchBase::C,
+            1 => PitchBase::D,
+            2 => PitchBase::E,
+            3 => PitchBase::F,
+            4 => PitchBase::G,
+            5 => PitchBase::A,
+            6 => PitchBase::B,
+            _ => unreachable!()
+        }
+    }
+}
+
+impl fmt::Display for PitchBase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PitchBase::C => "C",
+            PitchBase::D => "D",
+            PitchBase::E => "E",
+            PitchBase::F => "F",
+            PitchBase::G => "G",
+            PitchBase::A => "A",
+            PitchBase::B => "B",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PitchModifier {
-    #[strum(serialize="𝄫")]
     DoubleFlat,
-    #[strum(serialize="♭")]
     Flat,
-    #[strum(serialize="")]
+    #[default]
     Natural,
-    #[strum(serialize="♯")]
     Sharp,
-    #[strum(serialize="𝄪")]
     DoubleSharp,
 }
 
+impl fmt::Display for PitchModifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            PitchModifier::DoubleFlat => "𝄫",
+            PitchModifier::Flat => "♭",
+            PitchModifier::Natural => "",
+            PitchModifier::Sharp => "♯",
+            PitchModifier::DoubleSharp => "𝄪",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// The error when decoding a letter name or accidental from a number outside its encoding.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct InvalidCode(pub u8);
+
+impl fmt::Display for InvalidCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} isn't a valid code", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCode {}
+
+/// Encodes a letter name as the number of letters above C, so C is 0, D is 1, and B is 6.
+impl From<PitchBase> for u8 {
+    fn from(base: PitchBase) -> u8 {
+        base.steps_from_c()
+    }
+}
+
+/// Decodes a letter name from the number of letters above C, from 0 for C to 6 for B.
+impl TryFrom<u8> for PitchBase {
+    type Error = InvalidCode;
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        if code < 7 {
+            Ok(PitchBase::from_steps_from_c(code))
+        } else {
+            Err(InvalidCode(code))
+        }
+    }
+}
+
+/// Encodes an accidental from 0 for a double flat, through 2 for a natural, up to 4 for a double sharp.
+impl From<PitchModifier> for u8 {
+    fn from(modifier: PitchModifier) -> u8 {
+        match modifier {
+            PitchModifier::DoubleFlat => 0,
+            PitchModifier::Flat => 1,
+            PitchModifier::Natural => 2,
+            PitchModifier::Sharp => 3,
+            PitchModifier::DoubleSharp => 4,
+        }
+    }
+}
+
+/// Decodes an accidental from 0 for a double flat, through 2 for a natural, up to 4 for a double sharp.
+impl TryFrom<u8> for PitchModifier {
+    type Error = InvalidCode;
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(PitchModifier::DoubleFlat),
+            1 => Ok(PitchModifier::Flat),
+            2 => Ok(PitchModifier::Natural),
+            3 => Ok(PitchModifier::Sharp),
+            4 => Ok(PitchModifier::DoubleSharp),
+            _ => Err(InvalidCode(code)),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Note(pub PitchBase, pub PitchModifier);
 
 impl Note {
-    pub fn semitones_from_c(&self) -> i8 {
+    pub const C: Note = Note(PitchBase::C, PitchModifier::Natural);
+    pub const D: Note = Note(PitchBase::D, PitchModifier::Natural);
+    pub const E: Note = Note(PitchBase::E, PitchModifier::Natural);
+    pub const F: Note = Note(PitchBase::F, PitchModifier::Natural);
+    pub const G: Note = Note(PitchBase::G, PitchModifier::Natural);
+    pub const A: Note = Note(PitchBase::A, PitchModifier::Natural);
+    pub const B: Note = Note(PitchBase::B, PitchModifier::Natural);
+
+    pub const fn semitones_from_c(&self) -> i8 {
         let base = match self.0 {
             PitchBase::C => 0,
             PitchBase::D => 2,
@@ -60,25 +224,97 @@ impl Note {
         base + modifier
     }
 
+    /// Gets the pitch class of the note, from 0 for C up to 11 for B. Unlike `semitones_from_c`, this is never negative
+    /// or more than 11, so C♭♭ is 10 and B♯ is 0.
+    pub const fn pitch_class(&self) -> u8 {
+        self.semitones_from_c().rem_euclid(12) as u8
+    }
+
+    /// Gets a note from its pitch class, wrapping around past 11. The notes are spelled using sharps.
+    pub fn from_pitch_class(pitch_class: u8) -> Self {
+        Note::from_semitones_from_c((pitch_class % 12) as i8)
+    }
+
     /// Gets a note from the semitones above C. The notes are spelled using sharps.
     pub fn from_semitones_from_c(semitones: i8) -> Self {
-        let semitones = if semitones < 0 { semitones + 12 } else { semitones };
-        let semitones = semitones % 12;
-        match semitones {
-            0 => Note(PitchBase::C, PitchModifier::Natural),
-            1 => Note(PitchBase::C, PitchModifier::Sharp),
-            2 => Note(PitchBase::D, PitchModifier::Natural),
-            3 => Note(PitchBase::D, PitchModifier::Sharp),
-            4 => Note(PitchBase::E, PitchModifier::Natural),
-            5 => Note(PitchBase::F, PitchModifier::Natural),
-            6 => Note(PitchBase::F, PitchModifier::Sharp),
-            7 => Note(PitchBase::G, PitchModifier::Natural),
-            8 => Note(PitchBase::G, PitchModifier::Sharp),
-            9 => Note(PitchBase::A, PitchModifier::Natural),
-            10 => Note(PitchBase::A, PitchModifier::Sharp),
-            11 => Note(PitchBase::B, PitchModifier::Natural),
+        Note::from_semitones_from_c_with_preference(semitones, false)
+    }
+
+    /// Gets a note from the semitones above C, spelling the black keys with flats if `use_flats` is set and with
+    /// sharps otherwise.
+    pub fn from_semitones_from_c_with_preference(semitones: i8, use_flats: bool) -> Self {
+        let (base, modifier) = match (semitones.rem_euclid(12), use_flats) {
+            (0, _) => (PitchBase::C, PitchModifier::Natural),
+            (1, false) => (PitchBase::C, PitchModifier::Sharp),
+            (1, true) => (PitchBase::D, PitchModifier::Flat),
+            (2, _) => (PitchBase::D, PitchModifier::Natural),
+            (3, false) => (PitchBase::D, PitchModifier::Sharp),
+            (3, true) => (PitchBase::E, PitchModifier::Flat),
+            (4, _) => (PitchBase::E, PitchModifier::Natural),
+            (5, _) => (PitchBase::F, PitchModifier::Natural),
+            (6, false) => (PitchBase::F, PitchModifier::Sharp),
+            (6, true) => (PitchBase::G, PitchModifier::Flat),
+            (7, _) => (PitchBase::G, PitchModifier::Natural),
+            (8, false) => (PitchBase::G, PitchModifier::Sharp),
+            (8, true) => (PitchBase::A, PitchModifier::Flat),
+            (9, _) => (PitchBase::A, PitchModifier::Natural),
+            (10, false) => (PitchBase::A, PitchModifier::Sharp),
+            (10, true) => (PitchBase::B, PitchModifier::Flat),
+            (11, _) => (PitchBase::B, PitchModifier::Natural),
             _ => unreachable!()
+        };
+        Note(base, modifier)
+    }
+
+    /// Spells a note with the given letter name at the given number of semitones above C, if it can be reached with
+    /// at most a double sharp or double flat.
+    fn spelled(base: PitchBase, semitones: i8) -> Option<Self> {
+        let natural = Note(base, PitchModifier::Natural).semitones_from_c();
+        let mut difference = (semitones - natural).rem_euclid(12);
+        if difference > 6 {
+            difference -= 12;
         }
+        let modifier = match difference {
+            -2 => PitchModifier::DoubleFlat,
+            -1 => PitchModifier::Flat,
+            0 => PitchModifier::Natural,
+            1 => PitchModifier::Sharp,
+            2 => PitchModifier::DoubleSharp,
+            _ => return None,
+        };
+        Some(Note(base, modifier))
+    }
+
+    /// Gets every spelling of the note's pitch class that needs at most a double sharp or double flat, in letter order
+    /// from C, including the note itself.
+    pub fn enharmonic_equivalents(&self) -> Vec<Note> {
+        (0..7).filter_map(|steps| Note::spelled(PitchBase::from_steps_from_c(steps), self.semitones_from_c())).collect()
+    }
+
+    /// Respells the note on the given letter name, or `None` if that would need more than a double sharp or flat.
+    pub fn respell_as(&self, base: PitchBase) -> Option<Note> {
+        Note::spelled(base, self.semitones_from_c())
+    }
+
+    /// Gets the note an interval above this one, using the letter name called for by the interval's number. Falls
+    /// back to spelling with sharps if that letter would need more than a double sharp or flat.
+    fn add_interval(&self, interval: Interval) -> Self {
+        let base = PitchBase::from_steps_from_c(self.0.steps_from_c() + interval.steps() % 7);
+        let semitones = (self.semitones_from_c() + (interval.semitones() % 12) as i8).rem_euclid(12);
+        Note::spelled(base, semitones).unwrap_or_else(|| Note::from_semitones_from_c(semitones))
+    }
+
+    /// Gets the note an interval below this one, using the letter name called for by the interval's number.
+    fn sub_interval(&self, interval: Interval) -> Self {
+        let base = PitchBase::from_steps_from_c(self.0.steps_from_c() + 7 - interval.steps() % 7);
+        let semitones = (self.semitones_from_c() - (interval.semitones() % 12) as i8).rem_euclid(12);
+        Note::spelled(base, semitones).unwrap_or_else(|| Note::from_semitones_from_c(semitones))
+    }
+
+    /// Gets the interval spanning the shorter way between two pitch classes, which is never more than a tritone.
+    fn distance(&self, other: &Note) -> Interval {
+        let semitones = (self.pitch_class() + 12 - other.pitch_class()) % 12;
+        Interval::from_semitones(semitones.min(12 - semitones))
     }
 }
 
@@ -90,20 +326,113 @@ impl fmt::Display for Note {
 
 impl PartialEq for Note {
     fn eq(&self, other: &Note) -> bool {
-        self.semitones_from_c() == other.semitones_from_c()
+        self.pitch_class() == other.pitch_class()
     }
 }
 
 impl Eq for Note {}
 
+impl Hash for Note {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pitch_class().hash(state);
+    }
+}
+
+impl PartialOrd for Note {
+    fn partial_cmp(&self, other: &Note) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Notes are ordered by pitch class, from C up to B, so enharmonic notes compare equal and C♭ sorts after B♭.
+impl Ord for Note {
+    fn cmp(&self, other: &Note) -> core::cmp::Ordering {
+        self.pitch_class().cmp(&other.pitch_class())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An octave number in scientific pitch notation, where middle C starts octave 4 and each octave starts on C
+pub struct Octave(pub i8);
+
+impl Octave {
+    /// The lowest octave that can be heard, which is also the lowest octave MIDI can play.
+    pub const LOWEST: Octave = Octave(-1);
+    /// The highest octave that can be heard, which is also the highest octave MIDI can play.
+    pub const HIGHEST: Octave = Octave(9);
+
+    /// Creates an octave, or returns `None` if it lies outside of human hearing, from `LOWEST` to `HIGHEST`. Use `From`
+    /// to create an octave without checking.
+    pub fn new(octave: i8) -> Option<Self> {
+        let octave = Octave(octave);
+        if Octave::LOWEST <= octave && octave <= Octave::HIGHEST {
+            Some(octave)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<i8> for Octave {
+    fn from(octave: i8) -> Self {
+        Octave(octave)
+    }
+}
+
+impl From<Octave> for i8 {
+    fn from(octave: Octave) -> Self {
+        octave.0
+    }
+}
+
+impl fmt::Display for Octave {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Moves up by a number of octaves.
+impl ops::Add<i8> for Octave {
+    type Output = Octave;
+    fn add(self, other: i8) -> Self::Output {
+        Octave(self.0 + other)
+    }
+}
+
+/// Moves down by a number of octaves.
+impl ops::Sub<i8> for Octave {
+    type Output = Octave;
+    fn sub(self, other: i8) -> Self::Output {
+        Octave(self.0 - other)
+    }
+}
+
+/// Counts the octaves from one octave up to another.
+impl ops::Sub for Octave {
+    type Output = i8;
+    fn sub(self, other: Octave) -> Self::Output {
+        self.0 - other.0
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
-/// Pitch base, pitch modifier, and octave. For example, A♭3 would be `Pitch(PitchBase::A, PitchModifier::Flat, 3)`
-pub struct Pitch(pub Note, pub i8);
+/// Pitch base, pitch modifier, and octave. For example, A♭3 would be
+/// `Pitch(Note(PitchBase::A, PitchModifier::Flat), Octave(3))`
+pub struct Pitch(pub Note, pub Octave);
 
 impl Pitch {
-    pub fn semitones_from_middle_c(&self) -> i8 {
-        let octave_difference = (self.1 - 4) * 12;
-        self.0.semitones_from_c() + octave_difference
+    pub const MIDDLE_C: Pitch = Pitch(Note::C, Octave(4));
+
+    pub const fn semitones_from_middle_c(&self) -> i16 {
+        let octave_difference = ((self.1).0 as i16 - 4) * 12;
+        self.0.semitones_from_c() as i16 + octave_difference
+    }
+
+    /// Gets the signed number of semitones from this pitch up to `other`, counting whole octaves, so a descending major
+    /// tenth is -16. Unlike subtracting the pitches, this doesn't lose the direction or the octaves.
+    pub fn distance_to(&self, other: &Pitch) -> i16 {
+        other.semitones_from_middle_c() - self.semitones_from_middle_c()
     }
     pub fn from_semitones_from_middle_c(semitones: i8) -> Self {
         let mut octave_difference = 0;
@@ -112,14 +441,116 @@ impl Pitch {
             semitones += 12;
             octave_difference -= 1;
         }
-        while semitones > 12 {
+        while semitones >= 12 {
             semitones -= 12;
             octave_difference += 1;
         }
-        Pitch(Note::from_semitones_from_c(semitones), 4 + octave_difference)
+        Pitch(Note::from_semitones_from_c(semitones), Octave(4 + octave_difference))
+    }
+
+    /// Gets the pitch an interval above this one, using the letter name called for by the interval's number, or `None`
+    /// if it would be further from middle C than an `i8` of semitones can count.
+    pub fn checked_add_interval(&self, interval: Interval) -> Option<Self> {
+        let semitones = i8::try_from(self.semitones_from_middle_c() as i32 + interval.semitones() as i32).ok()?;
+        Some(Pitch::in_octave(self.0.add_interval(interval), semitones))
+    }
+
+    /// Gets the pitch an interval below this one, using the letter name called for by the interval's number, or `None`
+    /// if it would be further from middle C than an `i8` of semitones can count.
+    pub fn checked_sub_interval(&self, interval: Interval) -> Option<Self> {
+        let semitones = i8::try_from(self.semitones_from_middle_c() as i32 - interval.semitones() as i32).ok()?;
+        Some(Pitch::in_octave(self.0.sub_interval(interval), semitones))
+    }
+
+    fn add_interval(&self, interval: Interval) -> Self {
+        self.checked_add_interval(interval).expect("pitch is too far from middle C")
+    }
+
+    fn sub_interval(&self, interval: Interval) -> Self {
+        self.checked_sub_interval(interval).expect("pitch is too far from middle C")
+    }
+
+    fn moved_by(&self, semitones: i16) -> Self {
+        let semitones = i8::try_from(self.semitones_from_middle_c() + semitones).expect("pitch is too far from middle C");
+        Pitch::from_semitones_from_middle_c(semitones)
+    }
+
+    /// Gets the interval between two pitches, whichever is higher. The difference is taken in an `i32` since pitches at
+    /// the extremes of the `i8` range can be more than 127 semitones apart.
+    fn interval_to(&self, other: &Pitch) -> Interval {
+        let semitones = self.semitones_from_middle_c() as i32 - other.semitones_from_middle_c() as i32;
+        Interval::try_from_semitones(semitones.abs()).expect("pitches are never more than 255 semitones apart")
+    }
+
+    /// Puts a note in the octave that leaves it the given number of semitones from middle C.
+    fn in_octave(note: Note, semitones: i8) -> Self {
+        Pitch(note, Octave(4 + (semitones as i16 - note.semitones_from_c() as i16).div_euclid(12) as i8))
+    }
+
+    /// Writes the pitch in Helmholtz notation. The octave below middle C is written in lowercase, like `c`, and each
+    /// octave above adds a prime, so middle C is `c'`. The octave below that is in uppercase, like `C`, and each
+    /// octave below adds a comma, so A0 is `A,,`.
+    pub fn to_helmholtz(&self) -> String {
+        let letter = format!("{}", (self.0).0);
+        let octave = (self.1).0;
+        if octave >= 3 {
+            format!("{}{}{}", letter.to_lowercase(), (self.0).1, "'".repeat((octave - 3) as usize))
+        } else {
+            format!("{}{}{}", letter, (self.0).1, ",".repeat((2 - octave) as usize))
+        }
+    }
+
+    /// Gets the MIDI note number of the pitch, where middle C (C4) is 60. Returns `None` if the pitch is outside of
+    /// the MIDI range of 0 to 127.
+    pub fn to_midi(&self) -> Option<u8> {
+        let midi = 60 + self.semitones_from_middle_c();
+        if (0..=127).contains(&midi) {
+            Some(midi as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a pitch from a MIDI note number, where 60 is middle C (C4). The notes are spelled using sharps. Returns
+    /// `None` if the note number is above 127.
+    pub fn from_midi(midi: u8) -> Option<Self> {
+        if midi > 127 {
+            return None;
+        }
+        Some(Pitch(Note::from_semitones_from_c((midi % 12) as i8), Octave((midi / 12) as i8 - 1)))
+    }
+
+    /// Gets the equal-tempered frequency of the pitch in hertz, tuned so that A4 is `a4_hz`.
+    #[cfg(feature = "std")]
+    pub fn frequency(&self, a4_hz: f64) -> f64 {
+        let semitones_from_a4 = self.semitones_from_middle_c() as f64 - 9.0;
+        a4_hz * 2f64.powf(semitones_from_a4 / 12.0)
+    }
+
+    /// Gets the equal-tempered frequency of the pitch in hertz, using the standard tuning of A4 at 440 Hz.
+    #[cfg(feature = "std")]
+    pub fn frequency_standard(&self) -> f64 {
+        self.frequency(STANDARD_A4_HZ)
+    }
+
+    /// Gets the closest equal-tempered pitch to a frequency, tuned so that A4 is `a4_hz`, along with how many cents
+    /// the frequency is above (positive) or below (negative) that pitch. Returns `None` if the frequency isn't positive
+    /// or the pitch would be further from middle C than an `i8` of semitones can count.
+    #[cfg(feature = "std")]
+    pub fn nearest_from_frequency(hz: f64, a4_hz: f64) -> Option<(Self, f64)> {
+        let semitones_from_a4 = 12.0 * (hz / a4_hz).log2();
+        let nearest = semitones_from_a4.round();
+        if !nearest.is_finite() {
+            return None;
+        }
+        let semitones = i8::try_from(nearest as i32 + 9).ok()?;
+        Some((Pitch::from_semitones_from_middle_c(semitones), (semitones_from_a4 - nearest) * 100.0))
     }
 }
 
+/// The frequency of A4 in hertz under standard concert tuning.
+pub const STANDARD_A4_HZ: f64 = 440.0;
+
 impl fmt::Display for Pitch {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}{}", self.0, self.1)
@@ -134,370 +565,789 @@ impl PartialEq for Pitch {
 
 impl Eq for Pitch {}
 
+impl Hash for Pitch {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.semitones_from_middle_c().hash(state);
+    }
+}
+
 impl PartialOrd for Pitch {
-    fn partial_cmp(&self, other: &Pitch) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Pitch) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
 impl Ord for Pitch {
-    fn cmp(&self, other: &Pitch) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Pitch) -> core::cmp::Ordering {
         self.semitones_from_middle_c().cmp(&other.semitones_from_middle_c())
     }
 }
 
-// TODO: Enharmonic intervals
-#[derive(Clone, Copy, Debug, Display, Eq, PartialEq, Ord, PartialOrd)]
-pub enum Interval {
-    #[strum(serialize="unison")]
-    Unison,
-    #[strum(serialize="minor second")]
-    MinorSecond,
-    #[strum(serialize="major second")]
-    MajorSecond,
-    #[strum(serialize="minor third")]
-    MinorThird,
-    #[strum(serialize="major third")]
-    MajorThird,
-    #[strum(serialize="perfect fourth")]
-    PerfectFourth,
-    #[strum(serialize="tritone")]
-    Tritone,
-    #[strum(serialize="perfect fifth")]
-    PerfectFifth,
-    #[strum(serialize="minor sixth")]
-    MinorSixth,
-    #[strum(serialize="major sixth")]
-    MajorSixth,
-    #[strum(serialize="minor seventh")]
-    MinorSeventh,
-    #[strum(serialize="major seventh")]
-    MajorSeventh,
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The span of pitches a voice can sing, from its lowest pitch to its highest, inclusive
+pub struct Range(pub Pitch, pub Pitch);
+
+impl Range {
+    pub const SOPRANO: Range = Range(Pitch::MIDDLE_C, Pitch(Note::A, Octave(5)));
+    pub const ALTO: Range = Range(Pitch(Note::G, Octave(3)), Pitch(Note::D, Octave(5)));
+    pub const TENOR: Range = Range(Pitch(Note::C, Octave(3)), Pitch(Note::G, Octave(4)));
+    pub const BASS: Range = Range(Pitch(Note::E, Octave(2)), Pitch::MIDDLE_C);
+
+    /// Whether the pitch lies within the range, comparing enharmonically so that B♯3 is within a range ending on C4.
+    pub fn contains(&self, pitch: Pitch) -> bool {
+        self.0 <= pitch && pitch <= self.1
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} to {}", self.0, self.1)
+    }
+}
+
+/// Counts the letter names from `bottom` up to `top`, inclusive, ignoring accidentals. This is the number of the
+/// interval between them as written, so C up to D♭♭ is a second even though the notes sound the same, and G up to F
+/// is a seventh. Notes on the same letter give 1.
+pub fn diatonic_number(bottom: Note, top: Note) -> u8 {
+    (top.0.steps_from_c() + 7 - bottom.0.steps_from_c()) % 7 + 1
+}
+
+/// Transposes a sequence of pitches up by an interval. Each note moves by the interval's number of letter names, so
+/// E♭ up a minor third is G♭ rather than F♯.
+pub fn transpose(pitches: &[Pitch], by: Interval) -> Vec<Pitch> {
+    pitches.iter().map(|pitch| pitch + by).collect()
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntervalQuality {
+    DoublyDiminished,
+    Diminished,
+    Minor,
+    Perfect,
+    Major,
+    Augmented,
+    DoublyAugmented,
+}
+
+impl fmt::Display for IntervalQuality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            IntervalQuality::DoublyDiminished => "doubly diminished",
+            IntervalQuality::Diminished => "diminished",
+            IntervalQuality::Minor => "minor",
+            IntervalQuality::Perfect => "perfect",
+            IntervalQuality::Major => "major",
+            IntervalQuality::Augmented => "augmented",
+            IntervalQuality::DoublyAugmented => "doubly augmented",
+        };
+        f.write_str(name)
+    }
 }
 
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Interval quality and diatonic number, where a unison is 1, a second is 2, and so on. For example, a minor sixth
+/// would be `Interval(IntervalQuality::Minor, 6)`
+pub struct Interval(pub IntervalQuality, pub u8);
+
+/// The most common spelling of each simple interval, indexed by its number of semitones.
+const SIMPLE_INTERVALS: [Interval; 12] = [
+    Interval::UNISON,
+    Interval::MINOR_SECOND,
+    Interval::MAJOR_SECOND,
+    Interval::MINOR_THIRD,
+    Interval::MAJOR_THIRD,
+    Interval::PERFECT_FOURTH,
+    Interval::AUGMENTED_FOURTH,
+    Interval::PERFECT_FIFTH,
+    Interval::MINOR_SIXTH,
+    Interval::MAJOR_SIXTH,
+    Interval::MINOR_SEVENTH,
+    Interval::MAJOR_SEVENTH,
+];
+
+/// Semitones above the bottom note for the major and perfect intervals from a unison to a seventh.
+const MAJOR_SEMITONES: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
 impl Interval {
+    pub const UNISON: Interval = Interval(IntervalQuality::Perfect, 1);
+    pub const AUGMENTED_UNISON: Interval = Interval(IntervalQuality::Augmented, 1);
+    pub const MINOR_SECOND: Interval = Interval(IntervalQuality::Minor, 2);
+    pub const MAJOR_SECOND: Interval = Interval(IntervalQuality::Major, 2);
+    pub const AUGMENTED_SECOND: Interval = Interval(IntervalQuality::Augmented, 2);
+    pub const MINOR_THIRD: Interval = Interval(IntervalQuality::Minor, 3);
+    pub const MAJOR_THIRD: Interval = Interval(IntervalQuality::Major, 3);
+    pub const PERFECT_FOURTH: Interval = Interval(IntervalQuality::Perfect, 4);
+    pub const AUGMENTED_FOURTH: Interval = Interval(IntervalQuality::Augmented, 4);
+    pub const DIMINISHED_FIFTH: Interval = Interval(IntervalQuality::Diminished, 5);
+    pub const PERFECT_FIFTH: Interval = Interval(IntervalQuality::Perfect, 5);
+    pub const MINOR_SIXTH: Interval = Interval(IntervalQuality::Minor, 6);
+    pub const MAJOR_SIXTH: Interval = Interval(IntervalQuality::Major, 6);
+    pub const MINOR_SEVENTH: Interval = Interval(IntervalQuality::Minor, 7);
+    pub const MAJOR_SEVENTH: Interval = Interval(IntervalQuality::Major, 7);
+    pub const OCTAVE: Interval = Interval(IntervalQuality::Perfect, 8);
+
+    /// Gets every simple interval from a unison up to a major seventh, one for each number of semitones in ascending
+    /// order, spelled as `from_semitones` spells them.
+    pub fn all() -> &'static [Interval; 12] {
+        &SIMPLE_INTERVALS
+    }
+
+    /// Gets an interval from a number of semitones, using the most common spelling. A tritone is spelled as an
+    /// augmented fourth. Intervals larger than an octave are compound, so 16 semitones is a major tenth.
     pub fn from_semitones(semitones: u8) -> Self {
+        let octaves = semitones / 12;
         let semitones = semitones % 12;
 
-        match semitones {
-            0 => Interval::Unison,
-            1 => Interval::MinorSecond,
-            2 => Interval::MajorSecond,
-            3 => Interval::MinorThird,
-            4 => Interval::MajorThird,
-            5 => Interval::PerfectFourth,
-            6 => Interval::Tritone,
-            7 => Interval::PerfectFifth,
-            8 => Interval::MinorSixth,
-            9 => Interval::MajorSixth,
-            10 => Interval::MinorSeventh,
-            11 => Interval::MajorSeventh,
-            _ => unreachable!()
+        let Interval(quality, number) = SIMPLE_INTERVALS[semitones as usize];
+        Interval(quality, number + 7 * octaves)
+    }
+
+    /// Gets an interval from a number of semitones like `from_semitones`, or `None` if the number is negative or larger
+    /// than 255.
+    pub fn try_from_semitones(semitones: i32) -> Option<Self> {
+        u8::try_from(semitones).ok().map(Interval::from_semitones)
+    }
+
+    /// Gets the interval reduced to within an octave, so a major tenth becomes a major third and an octave becomes a
+    /// unison. Diminished octaves are left as they are, since a unison cannot be diminished.
+    pub fn simple(&self) -> Self {
+        let number = self.steps() % 7 + 1;
+        match self.0 {
+            IntervalQuality::Diminished | IntervalQuality::DoublyDiminished if number == 1 => Interval(self.0, 8),
+            _ => Interval(self.0, number),
         }
     }
 
-    pub fn semitones(&self) -> u8 {
-        match *self {
-            Interval::Unison => 0,
-            Interval::MinorSecond => 1,
-            Interval::MajorSecond => 2,
-            Interval::MinorThird => 3,
-            Interval::MajorThird => 4,
-            Interval::PerfectFourth => 5,
-            Interval::Tritone => 6,
-            Interval::PerfectFifth => 7,
-            Interval::MinorSixth => 8,
-            Interval::MajorSixth => 9,
-            Interval::MinorSeventh => 10,
-            Interval::MajorSeventh => 11,
+    /// Gets the ascending interval from `bottom` to `top`, spelled according to the letter names of the notes. For
+    /// example, F to B is an augmented fourth, while B to F is a diminished fifth. Returns `None` if the notes are
+    /// further apart than a doubly augmented or doubly diminished interval.
+    pub fn between(bottom: Note, top: Note) -> Option<Self> {
+        let steps = diatonic_number(bottom, top) - 1;
+        let semitones = ((top.pitch_class() + 12 - bottom.pitch_class()) % 12) as i8;
+
+        let mut deviation = semitones - MAJOR_SEMITONES[steps as usize];
+        if deviation > 6 {
+            deviation -= 12;
+        } else if deviation < -6 {
+            deviation += 12;
         }
+
+        // A "diminished unison" is really a diminished octave when ascending
+        let number = if steps == 0 && deviation < 0 { 8 } else { steps + 1 };
+        Self::from_deviation(number, deviation)
     }
 
-    pub fn inverse(&self) -> Self {
-        let semitones = self.semitones();
-        Self::from_semitones(12 - semitones)
+    /// Names the ascending interval from `bottom` to `top` by the letter names of the notes, so F to B is an "augmented
+    /// fourth" while B to F is a "diminished fifth". Notes too far apart to spell fall back to the most common name for
+    /// their distance.
+    pub fn name_for(bottom: Note, top: Note) -> String {
+        let interval = Self::between(bottom, top).unwrap_or_else(|| {
+            Self::from_semitones((top.pitch_class() + 12 - bottom.pitch_class()) % 12)
+        });
+        interval.to_string()
     }
-}
 
-impl ops::Add<i8> for Pitch {
-    type Output = Pitch;
-    fn add(self, other: i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other)
+    /// Whether unisons, fourths, fifths, and their compounds, which are perfect rather than major or minor.
+    fn is_perfect_number(number: u8) -> bool {
+        matches!(number.saturating_sub(1) % 7, 0 | 3 | 4)
     }
-}
-impl ops::Add<i8> for &Pitch {
-    type Output = Pitch;
-    fn add(self, other: i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other)
+
+    /// Counts the letter names the interval spans above its bottom note, so a unison is 0 and a third is 2. A number
+    /// of 0 is taken to be a unison.
+    fn steps(&self) -> u8 {
+        self.1.saturating_sub(1)
     }
-}
-impl ops::Add<&i8> for Pitch {
-    type Output = Pitch;
-    fn add(self, other: &i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other)
+
+    /// Gets an interval of the given number that is `deviation` semitones larger than the major or perfect interval.
+    fn from_deviation(number: u8, deviation: i8) -> Option<Self> {
+        let quality = if Self::is_perfect_number(number) {
+            match deviation {
+                -2 => IntervalQuality::DoublyDiminished,
+                -1 => IntervalQuality::Diminished,
+                0 => IntervalQuality::Perfect,
+                1 => IntervalQuality::Augmented,
+                2 => IntervalQuality::DoublyAugmented,
+                _ => return None,
+            }
+        } else {
+            match deviation {
+                -3 => IntervalQuality::DoublyDiminished,
+                -2 => IntervalQuality::Diminished,
+                -1 => IntervalQuality::Minor,
+                0 => IntervalQuality::Major,
+                1 => IntervalQuality::Augmented,
+                2 => IntervalQuality::DoublyAugmented,
+                _ => return None,
+            }
+        };
+        Some(Interval(quality, number))
     }
-}
-impl ops::Add<&i8> for &Pitch {
-    type Output = Pitch;
-    fn add(self, other: &i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other)
+
+    /// Gets how many semitones larger this interval is than the major or perfect interval of the same number. A
+    /// quality the number can't take is measured like the nearest one it can: a major or perfect interval counts as
+    /// either, and a minor perfect interval as diminished.
+    fn deviation(&self) -> i8 {
+        match (Self::is_perfect_number(self.1), self.0) {
+            (true, IntervalQuality::DoublyDiminished) => -2,
+            (true, IntervalQuality::Diminished) => -1,
+            (true, IntervalQuality::Perfect) | (true, IntervalQuality::Major) => 0,
+            (true, IntervalQuality::Minor) => -1,
+            (true, IntervalQuality::Augmented) => 1,
+            (true, IntervalQuality::DoublyAugmented) => 2,
+            (false, IntervalQuality::DoublyDiminished) => -3,
+            (false, IntervalQuality::Diminished) => -2,
+            (false, IntervalQuality::Minor) => -1,
+            (false, IntervalQuality::Major) | (false, IntervalQuality::Perfect) => 0,
+            (false, IntervalQuality::Augmented) => 1,
+            (false, IntervalQuality::DoublyAugmented) => 2,
+        }
     }
-}
 
-impl ops::Sub<i8> for Pitch {
-    type Output = Pitch;
-    fn sub(self, other: i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other)
+    /// Gets the usual shorthand for the interval, such as `m3` for a minor third or `P5` for a perfect fifth. Augmented
+    /// fourths and diminished fifths are both `TT`, for tritone. Compound intervals are abbreviated as their simple
+    /// interval, except that octaves stay `P8` rather than becoming `P1`. Intervals with a quality their number can't
+    /// take are abbreviated by their size.
+    pub fn abbrev(&self) -> &'static str {
+        let number = if self.1 <= 1 { 1 } else { (self.1 - 2) % 7 + 2 };
+        let names = match self.0 {
+            IntervalQuality::DoublyDiminished => ["", "dd2", "dd3", "dd4", "dd5", "dd6", "dd7", "dd8"],
+            IntervalQuality::Diminished => ["", "d2", "d3", "d4", "TT", "d6", "d7", "d8"],
+            IntervalQuality::Minor => ["", "m2", "m3", "", "", "m6", "m7", ""],
+            IntervalQuality::Perfect => ["P1", "", "", "P4", "P5", "", "", "P8"],
+            IntervalQuality::Major => ["", "M2", "M3", "", "", "M6", "M7", ""],
+            IntervalQuality::Augmented => ["A1", "A2", "A3", "TT", "A5", "A6", "A7", "A8"],
+            IntervalQuality::DoublyAugmented => ["AA1", "AA2", "AA3", "AA4", "AA5", "AA6", "AA7", "AA8"],
+        };
+        match names[(number - 1) as usize] {
+            "" => Interval::from_semitones(self.semitones()).abbrev(),
+            name => name,
+        }
     }
-}
-impl ops::Sub<i8> for &Pitch {
-    type Output = Pitch;
-    fn sub(self, other: i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other)
+
+    fn number_name(number: u8) -> String {
+        let name = match number {
+            1 => "unison",
+            2 => "second",
+            3 => "third",
+            4 => "fourth",
+            5 => "fifth",
+            6 => "sixth",
+            7 => "seventh",
+            8 => "octave",
+            9 => "ninth",
+            10 => "tenth",
+            11 => "eleventh",
+            12 => "twelfth",
+            13 => "thirteenth",
+            14 => "fourteenth",
+            15 => "fifteenth",
+            _ => {
+                let suffix = match (number % 10, number % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                return format!("{}{}", number, suffix);
+            }
+        };
+        name.to_string()
     }
-}
-impl ops::Sub<&i8> for Pitch {
-    type Output = Pitch;
-    fn sub(self, other: &i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other)
+
+    /// Gets the size of the interval in semitones. Diminished unisons, which would be smaller than nothing, are 0, and
+    /// intervals larger than 255 semitones are 255.
+    pub fn semitones(&self) -> u8 {
+        self.full_semitones().clamp(0, u8::MAX as i32) as u8
     }
-}
-impl ops::Sub<&i8> for &Pitch {
-    type Output = Pitch;
-    fn sub(self, other: &i8) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other)
+
+    /// Gets the size of the interval in semitones without narrowing it to fit a `u8`.
+    fn full_semitones(&self) -> i32 {
+        let simple = self.steps() % 7;
+        let octaves = self.steps() / 7;
+        MAJOR_SEMITONES[simple as usize] as i32 + 12 * octaves as i32 + self.deviation() as i32
     }
-}
 
-impl ops::Add for Interval {
-    type Output = Interval;
-    fn add(self, other: Interval) -> Self::Output {
-        let semitones = self.semitones() + other.semitones();
-        Interval::from_semitones(semitones)
+    /// Stacks two intervals like `+`, or returns `None` if the sum is larger than 255 semitones or has a number
+    /// larger than 255.
+    pub fn checked_add(self, other: Interval) -> Option<Interval> {
+        let semitones = u8::try_from(self.full_semitones().max(0) + other.full_semitones().max(0)).ok()?;
+        let number = u8::try_from(self.steps() as u32 + other.steps() as u32 + 1).ok()?;
+        let major = MAJOR_SEMITONES[((number - 1) % 7) as usize] as i32 + 12 * ((number - 1) / 7) as i32;
+        let spelled = i8::try_from(semitones as i32 - major).ok().and_then(|deviation| Interval::from_deviation(number, deviation));
+        Some(spelled.unwrap_or_else(|| Interval::from_semitones(semitones)))
     }
-}
-impl ops::Add<&Interval> for Interval {
-    type Output = Interval;
-    fn add(self, other: &Interval) -> Self::Output {
-        let semitones = self.semitones() + other.semitones();
-        Interval::from_semitones(semitones)
+
+    /// Whether the interval is a perfect consonance: a unison, perfect fifth, octave, or one of their compounds.
+    pub fn is_perfect_consonance(&self) -> bool {
+        let simple = self.simple();
+        simple == Interval::UNISON || simple == Interval::PERFECT_FIFTH
     }
-}
-impl ops::Add<Interval> for &Interval {
-    type Output = Interval;
-    fn add(self, other: Interval) -> Self::Output {
-        let semitones = self.semitones() + other.semitones();
-        Interval::from_semitones(semitones)
+
+    /// Whether the interval is an imperfect consonance: a major or minor third or sixth, or one of their compounds.
+    pub fn is_imperfect_consonance(&self) -> bool {
+        [Interval::MINOR_THIRD, Interval::MAJOR_THIRD, Interval::MINOR_SIXTH, Interval::MAJOR_SIXTH].contains(&self.simple())
     }
-}
-impl ops::Add<&Interval> for &Interval {
-    type Output = Interval;
-    fn add(self, other: &Interval) -> Self::Output {
-        let semitones = self.semitones() + other.semitones();
-        Interval::from_semitones(semitones)
+
+    /// Whether the interval is consonant in two-voice counterpoint. Seconds, fourths, sevenths, and any augmented or
+    /// diminished interval, including the tritone, are dissonant. Intervals are judged by their spelling, so a
+    /// diminished fourth is dissonant even though it sounds like a major third.
+    pub fn is_consonant(&self) -> bool {
+        self.is_perfect_consonance() || self.is_imperfect_consonance()
     }
-}
 
-impl ops::Add<Pitch> for Interval {
-    type Output = Pitch;
-    fn add(self, other: Pitch) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(other.semitones_from_middle_c() + self.semitones() as i8)
+    /// Gets the size of the interval in cents under equal temperament, where each semitone is 100 cents. See `Tuning`
+    /// for other temperaments.
+    pub fn cents(&self) -> f64 {
+        self.semitones() as f64 * 100.0
     }
-}
-impl ops::Add<&Pitch> for Interval {
-    type Output = Pitch;
-    fn add(self, other: &Pitch) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(other.semitones_from_middle_c() + self.semitones() as i8)
+
+    /// Gets the interval that makes up an octave with this one once it's reduced to a simple interval. The number
+    /// inverts to nine minus itself and the quality flips, so a major third inverts to a minor sixth and a diminished
+    /// fifth to an augmented fourth. Unisons and octaves both invert to a unison. Intervals with a quality their number
+    /// can't take fall back to inverting by semitones.
+    pub fn inverse(&self) -> Self {
+        let simple = self.simple();
+        let quality = match (Self::is_perfect_number(simple.1), simple.0) {
+            (true, IntervalQuality::Perfect) => Some(IntervalQuality::Perfect),
+            (false, IntervalQuality::Major) => Some(IntervalQuality::Minor),
+            (false, IntervalQuality::Minor) => Some(IntervalQuality::Major),
+            (_, IntervalQuality::Augmented) => Some(IntervalQuality::Diminished),
+            (_, IntervalQuality::Diminished) => Some(IntervalQuality::Augmented),
+            (_, IntervalQuality::DoublyAugmented) => Some(IntervalQuality::DoublyDiminished),
+            (_, IntervalQuality::DoublyDiminished) => Some(IntervalQuality::DoublyAugmented),
+            _ => None,
+        };
+        match quality {
+            Some(IntervalQuality::Perfect) if simple.1 == 1 => Interval::UNISON,
+            Some(quality) => Interval(quality, 9 - simple.1),
+            None => Self::from_semitones((12 - simple.semitones() % 12) % 12),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.0, Self::number_name(self.1))
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Interval) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Interval) -> core::cmp::Ordering {
+        (self.semitones(), self.1).cmp(&(other.semitones(), other.1))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An interval between two successive pitches of a melody, along with whether the melody rises or falls
+pub enum DirectedInterval {
+    Ascending(Interval),
+    Descending(Interval),
+}
+
+impl DirectedInterval {
+    /// Gets the interval moving from one pitch to the next. The interval is spelled from the letter names of the
+    /// pitches where possible, so D♯4 up to F4 is a diminished third. A repeated pitch is an ascending unison.
+    pub fn between(from: Pitch, to: Pitch) -> Self {
+        let semitones = to.semitones_from_middle_c() as i32 - from.semitones_from_middle_c() as i32;
+        let steps = Self::steps_from_middle_c(to) - Self::steps_from_middle_c(from);
+        let is_ascending = semitones > 0 || (semitones == 0 && steps >= 0);
+        let (semitones, steps) = if is_ascending { (semitones, steps) } else { (-semitones, -steps) };
+
+        let spelled = if steps >= 0 {
+            let major = MAJOR_SEMITONES[(steps % 7) as usize] as i32 + 12 * (steps / 7);
+            match (u8::try_from(steps + 1), i8::try_from(semitones - major)) {
+                (Ok(number), Ok(deviation)) => Interval::from_deviation(number, deviation),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        let interval = spelled.unwrap_or_else(|| {
+            Interval::try_from_semitones(semitones).expect("pitches are never more than 255 semitones apart")
+        });
+
+        if is_ascending {
+            DirectedInterval::Ascending(interval)
+        } else {
+            DirectedInterval::Descending(interval)
+        }
+    }
+
+    /// Counts letter names from middle C, so that C4 is 0, D4 is 1, and B3 is -1.
+    fn steps_from_middle_c(pitch: Pitch) -> i32 {
+        ((pitch.1).0 as i32 - 4) * 7 + (pitch.0).0.steps_from_c() as i32
+    }
+
+    pub fn interval(&self) -> Interval {
+        match self {
+            DirectedInterval::Ascending(interval) | DirectedInterval::Descending(interval) => *interval,
+        }
+    }
+
+    pub fn is_ascending(&self) -> bool {
+        matches!(self, DirectedInterval::Ascending(_))
+    }
+
+    /// Gets the number of semitones moved, which is negative when descending.
+    pub fn semitones(&self) -> i8 {
+        match self {
+            DirectedInterval::Ascending(interval) => interval.semitones() as i8,
+            DirectedInterval::Descending(interval) => -(interval.semitones() as i8),
+        }
+    }
+}
+
+/// Flips the direction of the interval, keeping its size and spelling.
+impl ops::Neg for DirectedInterval {
+    type Output = DirectedInterval;
+    fn neg(self) -> Self::Output {
+        match self {
+            DirectedInterval::Ascending(interval) => DirectedInterval::Descending(interval),
+            DirectedInterval::Descending(interval) => DirectedInterval::Ascending(interval),
+        }
+    }
+}
+impl ops::Neg for &DirectedInterval {
+    type Output = DirectedInterval;
+    fn neg(self) -> Self::Output {
+        -*self
+    }
+}
+
+impl fmt::Display for DirectedInterval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DirectedInterval::Ascending(interval) => write!(f, "ascending {}", interval),
+            DirectedInterval::Descending(interval) => write!(f, "descending {}", interval),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The way two voices move relative to each other from one note to the next
+pub enum Motion {
+    /// Both voices move in the same direction and stay the same generic interval apart
+    Parallel,
+    /// Both voices move in the same direction, but the interval between them changes
+    Similar,
+    /// The voices move in opposite directions
+    Contrary,
+    /// One voice moves while the other stays put, or neither moves
+    Oblique,
+}
+
+/// Classifies the motion of two voices, where one moves from `prev_a` to `cur_a` and the other from `prev_b` to
+/// `cur_b`.
+pub fn motion_type(prev_a: Pitch, cur_a: Pitch, prev_b: Pitch, cur_b: Pitch) -> Motion {
+    let motion_a = (cur_a.semitones_from_middle_c() - prev_a.semitones_from_middle_c()).signum();
+    let motion_b = (cur_b.semitones_from_middle_c() - prev_b.semitones_from_middle_c()).signum();
+
+    if motion_a == 0 || motion_b == 0 {
+        Motion::Oblique
+    } else if motion_a != motion_b {
+        Motion::Contrary
+    } else if (prev_a - prev_b).simple().1 == (cur_a - cur_b).simple().1 {
+        Motion::Parallel
+    } else {
+        Motion::Similar
+    }
+}
+
+impl ops::Add<i8> for Pitch {
+    type Output = Pitch;
+    fn add(self, other: i8) -> Self::Output {
+        self.moved_by(other as i16)
+    }
+}
+impl ops::Add<i8> for &Pitch {
+    type Output = Pitch;
+    fn add(self, other: i8) -> Self::Output {
+        self.moved_by(other as i16)
+    }
+}
+impl ops::Add<&i8> for Pitch {
+    type Output = Pitch;
+    fn add(self, other: &i8) -> Self::Output {
+        self.moved_by(*other as i16)
+    }
+}
+impl ops::Add<&i8> for &Pitch {
+    type Output = Pitch;
+    fn add(self, other: &i8) -> Self::Output {
+        self.moved_by(*other as i16)
+    }
+}
+
+impl ops::Sub<i8> for Pitch {
+    type Output = Pitch;
+    fn sub(self, other: i8) -> Self::Output {
+        self.moved_by(-(other as i16))
+    }
+}
+impl ops::Sub<i8> for &Pitch {
+    type Output = Pitch;
+    fn sub(self, other: i8) -> Self::Output {
+        self.moved_by(-(other as i16))
+    }
+}
+impl ops::Sub<&i8> for Pitch {
+    type Output = Pitch;
+    fn sub(self, other: &i8) -> Self::Output {
+        self.moved_by(-(*other as i16))
+    }
+}
+impl ops::Sub<&i8> for &Pitch {
+    type Output = Pitch;
+    fn sub(self, other: &i8) -> Self::Output {
+        self.moved_by(-(*other as i16))
+    }
+}
+
+/// Stacks two intervals, counting letter names as well as semitones so the sum keeps its spelling and doesn't wrap at
+/// the octave. A major third and a major third make an augmented fifth, and a major sixth and a perfect fifth make a
+/// major tenth. Sums too far from a major or perfect interval to spell fall back to the most common name for their
+/// size. Panics if the sum is too large to be an `Interval`; see `Interval::checked_add`.
+impl ops::Add for Interval {
+    type Output = Interval;
+    fn add(self, other: Interval) -> Self::Output {
+        self.checked_add(other).expect("interval sum is larger than 255 semitones")
+    }
+}
+impl ops::Add<&Interval> for Interval {
+    type Output = Interval;
+    fn add(self, other: &Interval) -> Self::Output {
+        self + *other
+    }
+}
+impl ops::Add<Interval> for &Interval {
+    type Output = Interval;
+    fn add(self, other: Interval) -> Self::Output {
+        *self + other
+    }
+}
+impl ops::Add<&Interval> for &Interval {
+    type Output = Interval;
+    fn add(self, other: &Interval) -> Self::Output {
+        *self + *other
+    }
+}
+
+impl ops::Add<Pitch> for Interval {
+    type Output = Pitch;
+    fn add(self, other: Pitch) -> Self::Output {
+        other.add_interval(self)
+    }
+}
+impl ops::Add<&Pitch> for Interval {
+    type Output = Pitch;
+    fn add(self, other: &Pitch) -> Self::Output {
+        other.add_interval(self)
     }
 }
 impl ops::Add<Pitch> for &Interval {
     type Output = Pitch;
     fn add(self, other: Pitch) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(other.semitones_from_middle_c() + self.semitones() as i8)
+        other.add_interval(*self)
     }
 }
 impl ops::Add<&Pitch> for &Interval {
     type Output = Pitch;
     fn add(self, other: &Pitch) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(other.semitones_from_middle_c() + self.semitones() as i8)
+        other.add_interval(*self)
     }
 }
 
 impl ops::Add<Interval> for Pitch {
     type Output = Pitch;
     fn add(self, other: Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other.semitones() as i8)
+        self.add_interval(other)
     }
 }
 impl ops::Add<&Interval> for Pitch {
     type Output = Pitch;
     fn add(self, other: &Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other.semitones() as i8)
+        self.add_interval(*other)
     }
 }
 impl ops::Add<Interval> for &Pitch {
     type Output = Pitch;
     fn add(self, other: Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other.semitones() as i8)
+        self.add_interval(other)
     }
 }
 impl ops::Add<&Interval> for &Pitch {
     type Output = Pitch;
     fn add(self, other: &Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() + other.semitones() as i8)
+        self.add_interval(*other)
     }
 }
 
 impl ops::Sub<Interval> for Pitch {
     type Output = Pitch;
     fn sub(self, other: Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other.semitones() as i8)
+        self.sub_interval(other)
     }
 }
 impl ops::Sub<&Interval> for Pitch {
     type Output = Pitch;
     fn sub(self, other: &Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other.semitones() as i8)
+        self.sub_interval(*other)
     }
 }
 impl ops::Sub<Interval> for &Pitch {
     type Output = Pitch;
     fn sub(self, other: Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other.semitones() as i8)
+        self.sub_interval(other)
     }
 }
 impl ops::Sub<&Interval> for &Pitch {
     type Output = Pitch;
     fn sub(self, other: &Interval) -> Self::Output {
-        Pitch::from_semitones_from_middle_c(self.semitones_from_middle_c() - other.semitones() as i8)
+        self.sub_interval(*other)
     }
 }
 
 impl ops::Add<Note> for Interval {
     type Output = Note;
     fn add(self, other: Note) -> Self::Output {
-        Note::from_semitones_from_c(other.semitones_from_c() + self.semitones() as i8)
+        other.add_interval(self)
     }
 }
 impl ops::Add<&Note> for Interval {
     type Output = Note;
     fn add(self, other: &Note) -> Self::Output {
-        Note::from_semitones_from_c(other.semitones_from_c() + self.semitones() as i8)
+        other.add_interval(self)
     }
 }
 impl ops::Add<Note> for &Interval {
     type Output = Note;
     fn add(self, other: Note) -> Self::Output {
-        Note::from_semitones_from_c(other.semitones_from_c() + self.semitones() as i8)
+        other.add_interval(*self)
     }
 }
 impl ops::Add<&Note> for &Interval {
     type Output = Note;
     fn add(self, other: &Note) -> Self::Output {
-        Note::from_semitones_from_c(other.semitones_from_c() + self.semitones() as i8)
+        other.add_interval(*self)
     }
 }
 
 impl ops::Add<Interval> for Note {
     type Output = Note;
     fn add(self, other: Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() + other.semitones() as i8)
+        self.add_interval(other)
     }
 }
 impl ops::Add<&Interval> for Note {
     type Output = Note;
     fn add(self, other: &Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() + other.semitones() as i8)
+        self.add_interval(*other)
     }
 }
 impl ops::Add<Interval> for &Note {
     type Output = Note;
     fn add(self, other: Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() + other.semitones() as i8)
+        self.add_interval(other)
     }
 }
 impl ops::Add<&Interval> for &Note {
     type Output = Note;
     fn add(self, other: &Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() + other.semitones() as i8)
+        self.add_interval(*other)
     }
 }
 
 impl ops::Sub<Interval> for Note {
     type Output = Note;
     fn sub(self, other: Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() - other.semitones() as i8)
+        self.sub_interval(other)
     }
 }
 impl ops::Sub<&Interval> for Note {
     type Output = Note;
     fn sub(self, other: &Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() - other.semitones() as i8)
+        self.sub_interval(*other)
     }
 }
 impl ops::Sub<Interval> for &Note {
     type Output = Note;
     fn sub(self, other: Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() - other.semitones() as i8)
+        self.sub_interval(other)
     }
 }
 impl ops::Sub<&Interval> for &Note {
     type Output = Note;
     fn sub(self, other: &Interval) -> Self::Output {
-        Note::from_semitones_from_c(self.semitones_from_c() - other.semitones() as i8)
+        self.sub_interval(*other)
+    }
+}
+
+/// Like subtracting pitches, subtracting notes doesn't depend on the order of the operands. Since notes have no octave,
+/// the result is the smaller of the two intervals between them, so both C - E and E - C are a major third.
+impl ops::Sub<Note> for Note {
+    type Output = Interval;
+    fn sub(self, other: Note) -> Self::Output {
+        self.distance(&other)
+    }
+}
+impl ops::Sub<&Note> for Note {
+    type Output = Interval;
+    fn sub(self, other: &Note) -> Self::Output {
+        self.distance(other)
+    }
+}
+impl ops::Sub<Note> for &Note {
+    type Output = Interval;
+    fn sub(self, other: Note) -> Self::Output {
+        self.distance(&other)
+    }
+}
+impl ops::Sub<&Note> for &Note {
+    type Output = Interval;
+    fn sub(self, other: &Note) -> Self::Output {
+        self.distance(other)
     }
 }
 
 impl ops::Sub<Pitch> for Pitch {
     type Output = Interval;
     fn sub(self, other: Pitch) -> Self::Output {
-        let (bottom, top) = if self < other {
-            (self, other)
-        } else {
-            (other, self)
-        };
-        let semitones = top.semitones_from_middle_c() - bottom.semitones_from_middle_c();
-        Interval::from_semitones(semitones as u8)
+        self.interval_to(&other)
     }
 }
 impl ops::Sub<&Pitch> for Pitch {
     type Output = Interval;
     fn sub(self, other: &Pitch) -> Self::Output {
-        let (bottom, top) = if &self < other {
-            (&self, other)
-        } else {
-            (other, &self)
-        };
-        let semitones = top.semitones_from_middle_c() - bottom.semitones_from_middle_c();
-        Interval::from_semitones(semitones as u8)
+        self.interval_to(other)
     }
 }
 impl ops::Sub<Pitch> for &Pitch {
     type Output = Interval;
     fn sub(self, other: Pitch) -> Self::Output {
-        let (bottom, top) = if self < &other {
-            (self, &other)
-        } else {
-            (&other, self)
-        };
-        let semitones = top.semitones_from_middle_c() - bottom.semitones_from_middle_c();
-        Interval::from_semitones(semitones as u8)
+        self.interval_to(&other)
     }
 }
 impl ops::Sub<&Pitch> for &Pitch {
     type Output = Interval;
     fn sub(self, other: &Pitch) -> Self::Output {
-        let (bottom, top) = if self < other {
-            (self, other)
-        } else {
-            (other, self)
-        };
-        let semitones = top.semitones_from_middle_c() - bottom.semitones_from_middle_c();
-        Interval::from_semitones(semitones as u8)
+        self.interval_to(other)
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScaleType {
     Ionian,
     Dorian,
@@ -512,36 +1362,142 @@ pub enum ScaleType {
     Pentatonic,
     PhrygianDominant,
     HungarianMinor,
+    NeapolitanMinor,
+    NeapolitanMajor,
+    Blues,
+    /// Octatonic scale starting with a half step
+    DiminishedHalfWhole,
+    /// Octatonic scale starting with a whole step
+    DiminishedWholeHalf,
+    Augmented,
+    /// A user-defined scale, given by the intervals between successive notes
+    Custom(Vec<Interval>),
 }
 
-lazy_static! {
-    static ref SCALES_MAP: HashMap<ScaleType, Vec<Interval>> = {
-        let mut map = HashMap::new();
-        map.insert(ScaleType::Ionian, vec![Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond]);
-        map.insert(ScaleType::Dorian, vec![Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond]);
-        map.insert(ScaleType::Phrygian, vec![Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond]);
-        map.insert(ScaleType::Lydian, vec![Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond]);
-        map.insert(ScaleType::Mixolydian, vec![Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond]);
-        map.insert(ScaleType::Aeolian, vec![Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond]);
-        map.insert(ScaleType::Locrian, vec![Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond]);
+/// Every named scale type, for looking a scale up by its step pattern or listing the scales on offer.
+const NAMED_SCALE_TYPES: [ScaleType; 19] = [
+    ScaleType::Ionian,
+    ScaleType::Dorian,
+    ScaleType::Phrygian,
+    ScaleType::Lydian,
+    ScaleType::Mixolydian,
+    ScaleType::Aeolian,
+    ScaleType::Locrian,
+    ScaleType::MelodicMinor,
+    ScaleType::HarmonicMinor,
+    ScaleType::WholeTone,
+    ScaleType::Pentatonic,
+    ScaleType::PhrygianDominant,
+    ScaleType::HungarianMinor,
+    ScaleType::NeapolitanMinor,
+    ScaleType::NeapolitanMajor,
+    ScaleType::Blues,
+    ScaleType::DiminishedHalfWhole,
+    ScaleType::DiminishedWholeHalf,
+    ScaleType::Augmented,
+];
 
-        map.insert(ScaleType::MelodicMinor, vec![Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond]);
-        map.insert(ScaleType::HarmonicMinor, vec![Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MinorThird, Interval::MinorSecond]);
+impl ScaleType {
+    /// Gets every named scale type, leaving out `Custom`, in the order they're declared.
+    pub fn all() -> &'static [ScaleType; 19] {
+        &NAMED_SCALE_TYPES
+    }
 
-        map.insert(ScaleType::PhrygianDominant, vec![Interval::MinorSecond, Interval::MinorThird, Interval::MinorSecond, Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond]);
-        map.insert(ScaleType::HungarianMinor, vec![Interval::MajorSecond, Interval::MinorSecond, Interval::MinorThird, Interval::MinorSecond, Interval::MinorSecond, Interval::MinorThird, Interval::MinorSecond]);
+    /// Gets the intervals between successive notes of the scale, from the root up to the root an octave above. The
+    /// patterns of the named scales are fixed, so this can be used in `const` contexts.
+    pub const fn step_pattern(&self) -> &[Interval] {
+        match self {
+            ScaleType::Custom(steps) => steps.as_slice(),
+            named => match named.named_step_pattern() {
+                Some(steps) => steps,
+                None => &[],
+            },
+        }
+    }
 
-        map.insert(ScaleType::WholeTone, vec![Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond]);
-        map.insert(ScaleType::Pentatonic, vec![Interval::MajorSecond, Interval::MinorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MajorSecond, Interval::MinorSecond]);
-        map
-    };
+    /// Gets the step pattern of a named scale, which lives for as long as the program does rather than being borrowed
+    /// from the scale type. Returns `None` for a `Custom` scale, whose steps are its own.
+    pub const fn named_step_pattern(&self) -> Option<&'static [Interval]> {
+        let steps: &'static [Interval] = match self {
+            ScaleType::Ionian => &[Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND],
+            ScaleType::Dorian => &[Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND],
+            ScaleType::Phrygian => &[Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND],
+            ScaleType::Lydian => &[Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND],
+            ScaleType::Mixolydian => &[Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND],
+            ScaleType::Aeolian => &[Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND],
+            ScaleType::Locrian => &[Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND],
+            ScaleType::MelodicMinor => &[Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND],
+            ScaleType::HarmonicMinor => &[Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::AUGMENTED_SECOND, Interval::MINOR_SECOND],
+            ScaleType::PhrygianDominant => &[Interval::MINOR_SECOND, Interval::AUGMENTED_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND],
+            ScaleType::HungarianMinor => &[Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::AUGMENTED_SECOND, Interval::MINOR_SECOND, Interval::MINOR_SECOND, Interval::AUGMENTED_SECOND, Interval::MINOR_SECOND],
+            ScaleType::NeapolitanMinor => &[Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::AUGMENTED_SECOND, Interval::MINOR_SECOND],
+            ScaleType::NeapolitanMajor => &[Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND],
+            ScaleType::WholeTone => &[Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval(IntervalQuality::Diminished, 3)],
+            ScaleType::Pentatonic => &[Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_THIRD, Interval::MAJOR_SECOND, Interval::MINOR_THIRD],
+            ScaleType::Blues => &[Interval::MINOR_THIRD, Interval::MAJOR_SECOND, Interval::AUGMENTED_UNISON, Interval::MINOR_SECOND, Interval::MINOR_THIRD, Interval::MAJOR_SECOND],
+            ScaleType::Augmented => &[Interval::AUGMENTED_SECOND, Interval::MINOR_SECOND, Interval::MINOR_THIRD, Interval::MINOR_SECOND, Interval::AUGMENTED_SECOND, Interval::MINOR_SECOND],
+            // With eight notes to seven letter names, one letter is used twice across an augmented unison
+            ScaleType::DiminishedHalfWhole => &[Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::AUGMENTED_UNISON, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND],
+            ScaleType::DiminishedWholeHalf => &[Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::AUGMENTED_UNISON, Interval::MAJOR_SECOND, Interval::MINOR_SECOND],
+            ScaleType::Custom(_) => return None,
+        };
+        Some(steps)
+    }
 }
 
+impl fmt::Display for ScaleType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ScaleType::Ionian => "Ionian",
+            ScaleType::Dorian => "Dorian",
+            ScaleType::Phrygian => "Phrygian",
+            ScaleType::Lydian => "Lydian",
+            ScaleType::Mixolydian => "Mixolydian",
+            ScaleType::Aeolian => "Aeolian",
+            ScaleType::Locrian => "Locrian",
+            ScaleType::MelodicMinor => "melodic minor",
+            ScaleType::HarmonicMinor => "harmonic minor",
+            ScaleType::WholeTone => "whole tone",
+            ScaleType::Pentatonic => "pentatonic",
+            ScaleType::PhrygianDominant => "Phrygian dominant",
+            ScaleType::HungarianMinor => "Hungarian minor",
+            ScaleType::NeapolitanMinor => "Neapolitan minor",
+            ScaleType::NeapolitanMajor => "Neapolitan major",
+            ScaleType::Blues => "blues",
+            ScaleType::DiminishedHalfWhole => "half-whole diminished",
+            ScaleType::DiminishedWholeHalf => "whole-half diminished",
+            ScaleType::Augmented => "augmented",
+            // A custom scale is named by its steps, such as "custom (M2 M2 m3 M2 m3)"
+            ScaleType::Custom(steps) => {
+                f.write_str("custom (")?;
+                for (idx, step) in steps.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(" ")?;
+                    }
+                    f.write_str(step.abbrev())?;
+                }
+                return f.write_str(")");
+            }
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scale(pub Note, pub ScaleType);
 
 impl Scale {
+    /// Creates a user-defined scale from its root and the intervals between successive notes.
+    pub fn from_intervals(root: Note, steps: Vec<Interval>) -> Self {
+        Scale(root, ScaleType::Custom(steps))
+    }
+
+    /// Gets the notes of the scale from the root up to the root again, one note per step of the scale's pattern, so a
+    /// pentatonic scale has six notes and an octatonic scale has nine. Every scale type has its step pattern given by an
+    /// exhaustive match, so this never panics, even for roots that call for awkward spellings.
     pub fn notes(&self) -> Vec<Note> {
-        let intervals = SCALES_MAP.get(&self.1).unwrap();
+        let intervals = self.1.step_pattern();
         let mut result = Vec::with_capacity(intervals.len() + 1);
 
         result.push(self.0);
@@ -554,6 +1510,256 @@ impl Scale {
 
         result
     }
+
+    /// Walks up the scale from `start`, normally a pitch on the root, for the given number of octaves. The octave number
+    /// goes up whenever the letter name passes B, so two octaves of C major run from C4 through C6.
+    pub fn pitches(&self, start: Pitch, octaves: u8) -> Vec<Pitch> {
+        let steps = self.1.step_pattern();
+        let mut result = Vec::with_capacity(steps.len() * octaves as usize + 1);
+
+        result.push(start);
+        let mut last_pitch = start;
+        for _ in 0..octaves {
+            for step in steps {
+                last_pitch = last_pitch + step;
+                result.push(last_pitch);
+            }
+        }
+
+        result
+    }
+
+    /// Gets the mode starting on a 1-based degree of the scale, so the 2nd mode of C Ionian is D Dorian. The mode is
+    /// given by name when its steps match a known scale, and as a custom scale otherwise. Returns `None` if the scale
+    /// doesn't have that many degrees.
+    pub fn mode(&self, degree: usize) -> Option<Scale> {
+        let root = self.note_at_degree(degree)?;
+        let mut steps = self.1.step_pattern().to_vec();
+        steps.rotate_left(degree - 1);
+
+        let scale_type = NAMED_SCALE_TYPES.iter().find(|scale_type| scale_type.step_pattern() == &steps[..]).cloned();
+        Some(Scale(root, scale_type.unwrap_or(ScaleType::Custom(steps))))
+    }
+
+    /// Gets the pitch classes of the scale as a bitmask, where bit 0 is set if C is in the scale, bit 1 if C♯ is, and so
+    /// on up to bit 11 for B. C major is `0b1010_1011_0101`.
+    pub fn pitch_class_mask(&self) -> u16 {
+        let mut pitch_class = self.0.pitch_class();
+        let mut mask = 0;
+        for step in self.1.step_pattern() {
+            mask |= 1 << pitch_class;
+            pitch_class = (pitch_class + step.semitones()) % 12;
+        }
+        mask
+    }
+
+    /// Whether the note is in the scale, comparing enharmonically, so F♯ is in G♭ major.
+    pub fn contains_note(&self, note: Note) -> bool {
+        self.pitch_class_mask() & (1 << note.pitch_class()) != 0
+    }
+
+    /// Whether the pitch's note is in the scale in any octave, comparing enharmonically.
+    pub fn contains_pitch(&self, pitch: Pitch) -> bool {
+        self.contains_note(pitch.0)
+    }
+
+    /// Gets the 1-based scale degree of a note, comparing enharmonically, or `None` if the note isn't in the scale.
+    pub fn degree_of(&self, note: Note) -> Option<usize> {
+        let notes = self.notes();
+        notes[..notes.len() - 1].iter().position(|n| *n == note).map(|idx| idx + 1)
+    }
+
+    /// Gets the note at a 1-based scale degree, or `None` if the scale doesn't have that many degrees.
+    pub fn note_at_degree(&self, degree: usize) -> Option<Note> {
+        let notes = self.notes();
+        if degree == 0 || degree >= notes.len() {
+            return None;
+        }
+        Some(notes[degree - 1])
+    }
+}
+
+/// Ranks every named scale on every root by how well it fits a melody, for when the scale to write counterpoint in
+/// isn't known. Each scale is scored by the fraction of the melody's notes it contains, from 0.0 to 1.0, and the best
+/// fits come first. Scales with the same score are ranked by how few of their notes the melody leaves unused, and then
+/// by whether they're rooted on the melody's last note or else its first, since melodies tend to end and begin on the
+/// tonic. Roots on the black keys are spelled with flats.
+pub fn infer_scale(notes: &[Note]) -> Vec<(Scale, f64)> {
+    let melody_mask = notes.iter().fold(0u16, |mask, note| mask | 1 << note.pitch_class());
+    let mut ranked = vec![];
+    for pitch_class in 0..12 {
+        let root = Note::from_semitones_from_c_with_preference(pitch_class, true);
+        for scale_type in NAMED_SCALE_TYPES.iter() {
+            let scale = Scale(root, scale_type.clone());
+            let contained = notes.iter().filter(|note| scale.contains_note(**note)).count();
+            let score = if notes.is_empty() { 0.0 } else { contained as f64 / notes.len() as f64 };
+            let unused = (scale.pitch_class_mask() & !melody_mask).count_ones();
+            let tonic = [notes.last(), notes.first()].iter().position(|note| *note == Some(&root)).unwrap_or(2);
+            ranked.push((scale, score, unused, tonic));
+        }
+    }
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.2.cmp(&b.2)).then(a.3.cmp(&b.3)));
+    ranked.into_iter().map(|(scale, score, _, _)| (scale, score)).collect()
+}
+
+/// The order sharps are added to a key signature in. Flats are added in the reverse order.
+const SHARPS_ORDER: [PitchBase; 7] = [PitchBase::F, PitchBase::C, PitchBase::G, PitchBase::D, PitchBase::A, PitchBase::E, PitchBase::B];
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A key signature given by its position on the circle of fifths, where positive numbers count sharps and negative
+/// numbers count flats
+pub struct KeySignature(pub i8);
+
+impl KeySignature {
+    /// Gets the key signature a scale is written in. Modes use the signature of their parent major scale, the minor
+    /// scales use that of the natural minor, and scales that don't belong to a key are written as if in the major key
+    /// of their root.
+    pub fn from_scale(scale: &Scale) -> Self {
+        let tonic = match scale.1 {
+            ScaleType::Ionian => scale.0,
+            ScaleType::Dorian => scale.0 - Interval::MAJOR_SECOND,
+            ScaleType::Phrygian | ScaleType::PhrygianDominant => scale.0 - Interval::MAJOR_THIRD,
+            ScaleType::Lydian => scale.0 - Interval::PERFECT_FOURTH,
+            ScaleType::Mixolydian => scale.0 - Interval::PERFECT_FIFTH,
+            ScaleType::Aeolian
+            | ScaleType::MelodicMinor
+            | ScaleType::HarmonicMinor
+            | ScaleType::HungarianMinor
+            | ScaleType::NeapolitanMinor
+            | ScaleType::NeapolitanMajor => scale.0 + Interval::MINOR_THIRD,
+            ScaleType::Locrian => scale.0 + Interval::MINOR_SECOND,
+            ScaleType::WholeTone
+            | ScaleType::Pentatonic
+            | ScaleType::Blues
+            | ScaleType::DiminishedHalfWhole
+            | ScaleType::DiminishedWholeHalf
+            | ScaleType::Augmented
+            | ScaleType::Custom(_) => scale.0,
+        };
+
+        let position = match tonic.0 {
+            PitchBase::F => -1,
+            PitchBase::C => 0,
+            PitchBase::G => 1,
+            PitchBase::D => 2,
+            PitchBase::A => 3,
+            PitchBase::E => 4,
+            PitchBase::B => 5,
+        };
+        let modifier = match tonic.1 {
+            PitchModifier::DoubleFlat => -2,
+            PitchModifier::Flat => -1,
+            PitchModifier::Natural => 0,
+            PitchModifier::Sharp => 1,
+            PitchModifier::DoubleSharp => 2,
+        };
+        KeySignature(position + 7 * modifier)
+    }
+
+    /// Gets the number of sharps, or the negated number of flats, as used by MusicXML's `<fifths>` element.
+    pub fn fifths(&self) -> i8 {
+        self.0
+    }
+
+    /// Gets the notes the key signature alters, in the order they're written. Keys past seven sharps or flats wrap
+    /// around to double sharps or double flats.
+    pub fn accidentals(&self) -> Vec<Note> {
+        let count = self.0.unsigned_abs() as usize;
+        (0..count)
+            .map(|idx| {
+                if self.0 > 0 {
+                    let modifier = if idx < 7 { PitchModifier::Sharp } else { PitchModifier::DoubleSharp };
+                    Note(SHARPS_ORDER[idx % 7], modifier)
+                } else {
+                    let modifier = if idx < 7 { PitchModifier::Flat } else { PitchModifier::DoubleFlat };
+                    Note(SHARPS_ORDER[6 - idx % 7], modifier)
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Dominant7,
+    Major7,
+    Minor7,
+    HalfDiminished7,
+    Diminished7,
+}
+
+impl ChordQuality {
+    /// Gets the intervals of each chord tone above the root, starting with the root itself.
+    pub const fn intervals(&self) -> &'static [Interval] {
+        match self {
+            ChordQuality::Major => &[Interval::UNISON, Interval::MAJOR_THIRD, Interval::PERFECT_FIFTH],
+            ChordQuality::Minor => &[Interval::UNISON, Interval::MINOR_THIRD, Interval::PERFECT_FIFTH],
+            ChordQuality::Diminished => &[Interval::UNISON, Interval::MINOR_THIRD, Interval::DIMINISHED_FIFTH],
+            ChordQuality::Augmented => &[Interval::UNISON, Interval::MAJOR_THIRD, Interval(IntervalQuality::Augmented, 5)],
+            ChordQuality::Dominant7 => &[Interval::UNISON, Interval::MAJOR_THIRD, Interval::PERFECT_FIFTH, Interval::MINOR_SEVENTH],
+            ChordQuality::Major7 => &[Interval::UNISON, Interval::MAJOR_THIRD, Interval::PERFECT_FIFTH, Interval::MAJOR_SEVENTH],
+            ChordQuality::Minor7 => &[Interval::UNISON, Interval::MINOR_THIRD, Interval::PERFECT_FIFTH, Interval::MINOR_SEVENTH],
+            ChordQuality::HalfDiminished7 => &[Interval::UNISON, Interval::MINOR_THIRD, Interval::DIMINISHED_FIFTH, Interval::MINOR_SEVENTH],
+            ChordQuality::Diminished7 => &[Interval::UNISON, Interval::MINOR_THIRD, Interval::DIMINISHED_FIFTH, Interval(IntervalQuality::Diminished, 7)],
+        }
+    }
+}
+
+const CHORD_QUALITIES: [ChordQuality; 9] = [
+    ChordQuality::Major,
+    ChordQuality::Minor,
+    ChordQuality::Diminished,
+    ChordQuality::Augmented,
+    ChordQuality::Dominant7,
+    ChordQuality::Major7,
+    ChordQuality::Minor7,
+    ChordQuality::HalfDiminished7,
+    ChordQuality::Diminished7,
+];
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A chord given by its root, quality, and inversion, where 0 is root position, 1 is first inversion, and so on
+pub struct Chord(pub Note, pub ChordQuality, pub u8);
+
+impl Chord {
+    /// Gets the notes of the chord from the bass up.
+    pub fn notes(&self) -> Vec<Note> {
+        let mut notes: Vec<Note> = self.1.intervals().iter().map(|interval| self.0 + interval).collect();
+        let inversion = self.2 as usize % notes.len();
+        notes.rotate_left(inversion);
+        notes
+    }
+
+    /// Names the chord made up of the given notes, comparing enharmonically. The first note is taken to be the bass,
+    /// but the rest may come in any order and may be repeated. Returns `None` if the notes don't make up a known chord.
+    pub fn identify(notes: &[Note]) -> Option<Self> {
+        let bass = *notes.first()?;
+        let mut pitch_classes: Vec<Note> = vec![];
+        for note in notes {
+            if !pitch_classes.contains(note) {
+                pitch_classes.push(*note);
+            }
+        }
+
+        // Try the bass as the root first, so that symmetrical chords are named in root position
+        for root in &pitch_classes {
+            for quality in CHORD_QUALITIES.iter() {
+                let chord_notes = Chord(*root, *quality, 0).notes();
+                if chord_notes.len() == pitch_classes.len() && pitch_classes.iter().all(|note| chord_notes.contains(note)) {
+                    let inversion = chord_notes.iter().position(|note| *note == bass).unwrap();
+                    return Some(Chord(*root, *quality, inversion as u8));
+                }
+            }
+        }
+        None
+    }
 }
 
 #[cfg(test)]
@@ -563,51 +1769,434 @@ mod tests {
     #[test]
     fn semitones_from_c() {
         // C natural is 0 semitones above C
-        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 4).0.semitones_from_c(), 0);
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)).0.semitones_from_c(), 0);
         // D natural is 2 semitones above C
-        assert_eq!(Pitch(Note(PitchBase::D, PitchModifier::Natural), 0).0.semitones_from_c(), 2);
+        assert_eq!(Pitch(Note(PitchBase::D, PitchModifier::Natural), Octave(0)).0.semitones_from_c(), 2);
         // D# is 3 semitones above C
-        assert_eq!(Pitch(Note(PitchBase::D, PitchModifier::Sharp), 4).0.semitones_from_c(), 3);
+        assert_eq!(Pitch(Note(PitchBase::D, PitchModifier::Sharp), Octave(4)).0.semitones_from_c(), 3);
+    }
+
+    #[test]
+    fn numeric_codes() {
+        for code in 0..7 {
+            assert_eq!(u8::from(PitchBase::try_from(code).unwrap()), code);
+        }
+        for code in 0..5 {
+            assert_eq!(u8::from(PitchModifier::try_from(code).unwrap()), code);
+        }
+        assert_eq!(format!("{}", PitchBase::try_from(4).unwrap()), "G");
+        assert_eq!(format!("{}", PitchModifier::try_from(1).unwrap()), "♭");
+        assert_eq!(u8::from(PitchBase::B), 6);
+        assert_eq!(u8::from(PitchModifier::Natural), 2);
+        assert_eq!(PitchBase::try_from(7).unwrap_err(), InvalidCode(7));
+        assert_eq!(PitchModifier::try_from(5).unwrap_err(), InvalidCode(5));
+        // Letter and accidental are encoded separately, so the spelling survives a round trip
+        let b_sharp = Note(PitchBase::B, PitchModifier::Sharp);
+        let (letter, accidental): (u8, u8) = (b_sharp.0.into(), b_sharp.1.into());
+        let decoded = Note(PitchBase::try_from(letter).unwrap(), PitchModifier::try_from(accidental).unwrap());
+        assert_eq!(format!("{}", decoded), "B♯");
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(Note::C, Note(PitchBase::C, PitchModifier::Natural));
+        assert_eq!(Note::F, Note(PitchBase::F, PitchModifier::Natural));
+        assert_eq!(format!("{}", Note::B), "B");
+        assert_eq!(Pitch::MIDDLE_C, Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)));
+        assert_eq!(Pitch::MIDDLE_C.semitones_from_middle_c(), 0);
+        assert_eq!(Pitch::MIDDLE_C.to_midi(), Some(60));
+        assert_eq!(format!("{}", PitchModifier::default()), "");
+        assert_eq!(Note(PitchBase::A, PitchModifier::default()), Note::A);
+    }
+
+    #[test]
+    fn pitch_classes() {
+        assert_eq!(Note(PitchBase::C, PitchModifier::Natural).pitch_class(), 0);
+        assert_eq!(Note(PitchBase::A, PitchModifier::Sharp).pitch_class(), 10);
+        // Spellings that fall outside of C to B wrap around
+        assert_eq!(Note(PitchBase::C, PitchModifier::DoubleFlat).semitones_from_c(), -2);
+        assert_eq!(Note(PitchBase::C, PitchModifier::DoubleFlat).pitch_class(), 10);
+        assert_eq!(Note(PitchBase::C, PitchModifier::Flat).pitch_class(), 11);
+        assert_eq!(Note(PitchBase::B, PitchModifier::Sharp).pitch_class(), 0);
+        assert_eq!(Note(PitchBase::B, PitchModifier::DoubleSharp).pitch_class(), 1);
+
+        assert!(matches!(Note::from_pitch_class(3), Note(PitchBase::D, PitchModifier::Sharp)));
+        assert!(matches!(Note::from_pitch_class(11), Note(PitchBase::B, PitchModifier::Natural)));
+        assert!(matches!(Note::from_pitch_class(12), Note(PitchBase::C, PitchModifier::Natural)));
+        for pitch_class in 0..12 {
+            assert_eq!(Note::from_pitch_class(pitch_class).pitch_class(), pitch_class);
+        }
+    }
+
+    #[test]
+    fn spelling_preference() {
+        // Semitone 3 is E flat when flats are preferred, and D sharp otherwise
+        assert!(matches!(Note::from_semitones_from_c_with_preference(3, true), Note(PitchBase::E, PitchModifier::Flat)));
+        assert!(matches!(Note::from_semitones_from_c_with_preference(3, false), Note(PitchBase::D, PitchModifier::Sharp)));
+        assert!(matches!(Note::from_semitones_from_c(3), Note(PitchBase::D, PitchModifier::Sharp)));
+        // White keys are spelled the same either way
+        assert!(matches!(Note::from_semitones_from_c_with_preference(4, true), Note(PitchBase::E, PitchModifier::Natural)));
+        assert!(matches!(Note::from_semitones_from_c_with_preference(-1, true), Note(PitchBase::B, PitchModifier::Natural)));
+        assert!(matches!(Note::from_semitones_from_c_with_preference(-11, true), Note(PitchBase::D, PitchModifier::Flat)));
+        // The semitone counts are usable in constants
+        const F_SHARP: i8 = Note(PitchBase::F, PitchModifier::Sharp).semitones_from_c();
+        const A3: i16 = Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(3)).semitones_from_middle_c();
+        assert_eq!((F_SHARP, A3), (6, -3));
     }
 
     #[test]
     fn enharmonic_equivalents() {
         // C natural is enharmonic to D double flat
-        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 4), Pitch(Note(PitchBase::D, PitchModifier::DoubleFlat), 4));
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)), Pitch(Note(PitchBase::D, PitchModifier::DoubleFlat), Octave(4)));
         // E natural is enharmonic to F flat
-        assert_eq!(Pitch(Note(PitchBase::E, PitchModifier::Natural), 2), Pitch(Note(PitchBase::F, PitchModifier::Flat), 2));
+        assert_eq!(Pitch(Note(PitchBase::E, PitchModifier::Natural), Octave(2)), Pitch(Note(PitchBase::F, PitchModifier::Flat), Octave(2)));
         // D sharp is enharmonic to E flat
-        assert_eq!(Pitch(Note(PitchBase::D, PitchModifier::Sharp), 2), Pitch(Note(PitchBase::E, PitchModifier::Flat), 2));
+        assert_eq!(Pitch(Note(PitchBase::D, PitchModifier::Sharp), Octave(2)), Pitch(Note(PitchBase::E, PitchModifier::Flat), Octave(2)));
         // C natural is enharmonic to B sharp
-        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 3), Pitch(Note(PitchBase::B, PitchModifier::Sharp), 2));
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(3)), Pitch(Note(PitchBase::B, PitchModifier::Sharp), Octave(2)));
         // Enharmonic pitches at different octaves are not equal
-        assert_ne!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 2), Pitch(Note(PitchBase::B, PitchModifier::Sharp), 2));
+        assert_ne!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(2)), Pitch(Note(PitchBase::B, PitchModifier::Sharp), Octave(2)));
+    }
+
+    #[test]
+    fn respelling() {
+        let names = |note: Note| note.enharmonic_equivalents().iter().map(|n| format!("{}", n)).collect::<Vec<_>>();
+        assert_eq!(names(Note::C), ["C", "D𝄫", "B♯"]);
+        assert_eq!(names(Note(PitchBase::F, PitchModifier::Sharp)), ["E𝄪", "F♯", "G♭"]);
+        assert_eq!(names(Note(PitchBase::G, PitchModifier::Flat)), names(Note(PitchBase::F, PitchModifier::Sharp)));
+        // G♯ has no spelling with a double accidental
+        assert_eq!(names(Note(PitchBase::A, PitchModifier::Flat)), ["G♯", "A♭"]);
+
+        let respelled = Note(PitchBase::F, PitchModifier::Sharp).respell_as(PitchBase::E);
+        assert!(matches!(respelled, Some(Note(PitchBase::E, PitchModifier::DoubleSharp))));
+        assert!(matches!(Note::C.respell_as(PitchBase::B), Some(Note(PitchBase::B, PitchModifier::Sharp))));
+        assert!(Note(PitchBase::F, PitchModifier::Sharp).respell_as(PitchBase::C).is_none());
+    }
+
+    #[test]
+    fn large_intervals() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        assert_eq!(Interval::try_from_semitones(28), Some(Interval(IntervalQuality::Major, 17)));
+        assert_eq!(Interval::try_from_semitones(255), Some(Interval::from_semitones(255)));
+        assert_eq!(Interval::try_from_semitones(-1), None);
+        assert_eq!(Interval::try_from_semitones(256), None);
+        // Two octaves and a major third apart, in either order
+        assert_eq!(pitch("C3") - pitch("E5"), Interval(IntervalQuality::Major, 17));
+        assert_eq!(pitch("E5") - pitch("C3"), Interval(IntervalQuality::Major, 17));
+        assert_eq!(DirectedInterval::between(pitch("E5"), pitch("C3")), DirectedInterval::Descending(Interval(IntervalQuality::Major, 17)));
+        // Pitches further apart than an i8 can hold don't overflow
+        let low = Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(-6));
+        let high = Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(14));
+        assert_eq!((high - low).semitones(), 240);
+        assert_eq!(DirectedInterval::between(low, high), DirectedInterval::Ascending(Interval(IntervalQuality::Perfect, 141)));
+    }
+
+    #[test]
+    fn note_subtraction() {
+        let c = Note(PitchBase::C, PitchModifier::Natural);
+        let e = Note(PitchBase::E, PitchModifier::Natural);
+        // Matches subtracting the pitches in either order
+        assert_eq!(c - e, Interval::MAJOR_THIRD);
+        assert_eq!(e - c, Interval::MAJOR_THIRD);
+        assert_eq!(Pitch(c, Octave(4)) - Pitch(e, Octave(4)), c - e);
+        // The shorter way round is taken, so C to A is a minor third rather than a major sixth
+        assert_eq!(c - Note(PitchBase::A, PitchModifier::Natural), Interval::MINOR_THIRD);
+        assert_eq!(c - Note(PitchBase::F, PitchModifier::Sharp), Interval::AUGMENTED_FOURTH);
+        assert_eq!(Note(PitchBase::B, PitchModifier::Sharp) - c, Interval::UNISON);
+    }
+
+    #[test]
+    fn note_ordering() {
+        let c = Note(PitchBase::C, PitchModifier::Natural);
+        let e = Note(PitchBase::E, PitchModifier::Natural);
+        let g = Note(PitchBase::G, PitchModifier::Natural);
+        let mut notes = vec![g, c, e];
+        notes.sort();
+        assert_eq!(notes, vec![c, e, g]);
+        // Enharmonic notes are neither greater nor less than each other
+        assert_eq!(Note(PitchBase::F, PitchModifier::Sharp).cmp(&Note(PitchBase::G, PitchModifier::Flat)), core::cmp::Ordering::Equal);
+        // B sharp is the same pitch class as C, and C flat the same as B
+        assert!(Note(PitchBase::B, PitchModifier::Sharp) < Note(PitchBase::C, PitchModifier::Sharp));
+        assert!(Note(PitchBase::C, PitchModifier::Flat) > Note(PitchBase::B, PitchModifier::Flat));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hashing() {
+        use std::collections::HashSet;
+
+        // C and B♯ are the same pitch class
+        let mut notes = HashSet::new();
+        notes.insert(Note(PitchBase::C, PitchModifier::Natural));
+        notes.insert(Note(PitchBase::B, PitchModifier::Sharp));
+        assert_eq!(notes.len(), 1);
+        // C♭ and B are the same pitch class
+        notes.insert(Note(PitchBase::C, PitchModifier::Flat));
+        notes.insert(Note(PitchBase::B, PitchModifier::Natural));
+        assert_eq!(notes.len(), 2);
+
+        // Enharmonic pitches collapse, but the same note in different octaves doesn't
+        let mut pitches = HashSet::new();
+        pitches.insert(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(3)));
+        pitches.insert(Pitch(Note(PitchBase::B, PitchModifier::Sharp), Octave(2)));
+        pitches.insert(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)));
+        assert_eq!(pitches.len(), 2);
     }
 
     #[test]
     fn inversions() {
         // The inversion of the unison is the unison
-        assert_eq!(Interval::Unison.inverse(), Interval::Unison);
+        assert_eq!(Interval::UNISON.inverse(), Interval::UNISON);
         // The inversion of a major third is a minor sixth
-        assert_eq!(Interval::MajorThird.inverse(), Interval::MinorSixth);
-        // The inversion of a tritone is the tritone
-        assert_eq!(Interval::Tritone.inverse(), Interval::Tritone);
+        assert_eq!(Interval::MAJOR_THIRD.inverse(), Interval::MINOR_SIXTH);
+        // The two spellings of the tritone invert to each other
+        assert_eq!(Interval::AUGMENTED_FOURTH.inverse(), Interval::DIMINISHED_FIFTH);
+        assert_eq!(Interval::DIMINISHED_FIFTH.inverse(), Interval::AUGMENTED_FOURTH);
         // The inversion of an inversion is itself
-        assert_eq!(Interval::MajorSeventh.inverse().inverse(), Interval::MajorSeventh);
+        assert_eq!(Interval::MAJOR_SEVENTH.inverse().inverse(), Interval::MAJOR_SEVENTH);
+        // Augmented and diminished intervals swap, keeping their numbers summing to nine
+        assert_eq!(Interval::AUGMENTED_SECOND.inverse(), Interval(IntervalQuality::Diminished, 7));
+        assert_eq!(Interval(IntervalQuality::Diminished, 7).inverse(), Interval::AUGMENTED_SECOND);
+        assert_eq!(Interval(IntervalQuality::Augmented, 6).inverse(), Interval(IntervalQuality::Diminished, 3));
+        assert_eq!(Interval(IntervalQuality::DoublyAugmented, 4).inverse(), Interval(IntervalQuality::DoublyDiminished, 5));
+        assert_eq!(Interval::AUGMENTED_UNISON.inverse(), Interval(IntervalQuality::Diminished, 8));
+        assert_eq!(Interval(IntervalQuality::Diminished, 8).inverse(), Interval::AUGMENTED_UNISON);
+        // Perfect intervals stay perfect, and octaves and compounds are reduced first
+        assert_eq!(Interval::PERFECT_FOURTH.inverse(), Interval::PERFECT_FIFTH);
+        assert_eq!(Interval::OCTAVE.inverse(), Interval::UNISON);
+        assert_eq!(Interval(IntervalQuality::Major, 10).inverse(), Interval::MINOR_SIXTH);
+        // An interval and its inversion always span an octave
+        for semitones in 0..12 {
+            let interval = Interval::from_semitones(semitones);
+            assert_eq!((interval.semitones() + interval.inverse().semitones()) % 12, 0);
+        }
+    }
+
+    #[test]
+    fn intervals_between_notes() {
+        // C to E is a major third
+        assert_eq!(Interval::between(Note(PitchBase::C, PitchModifier::Natural), Note(PitchBase::E, PitchModifier::Natural)), Some(Interval::MAJOR_THIRD));
+        // F to B is an augmented fourth
+        assert_eq!(Interval::between(Note(PitchBase::F, PitchModifier::Natural), Note(PitchBase::B, PitchModifier::Natural)), Some(Interval::AUGMENTED_FOURTH));
+        // B to F is a diminished fifth
+        assert_eq!(Interval::between(Note(PitchBase::B, PitchModifier::Natural), Note(PitchBase::F, PitchModifier::Natural)), Some(Interval::DIMINISHED_FIFTH));
+        // C to D♯ is an augmented second, not a minor third
+        assert_eq!(Interval::between(Note(PitchBase::C, PitchModifier::Natural), Note(PitchBase::D, PitchModifier::Sharp)), Some(Interval(IntervalQuality::Augmented, 2)));
+        // G to F is a minor seventh, even though F is below G in the octave
+        assert_eq!(Interval::between(Note(PitchBase::G, PitchModifier::Natural), Note(PitchBase::F, PitchModifier::Natural)), Some(Interval::MINOR_SEVENTH));
+        // C♯ to C is a diminished octave
+        assert_eq!(Interval::between(Note(PitchBase::C, PitchModifier::Sharp), Note(PitchBase::C, PitchModifier::Natural)), Some(Interval(IntervalQuality::Diminished, 8)));
+        // G♭ to C♯ is a doubly augmented fourth
+        assert_eq!(Interval::between(Note(PitchBase::G, PitchModifier::Flat), Note(PitchBase::C, PitchModifier::Sharp)), Some(Interval(IntervalQuality::DoublyAugmented, 4)));
+        // C𝄫 to C♯ is too far apart to be spelled as a unison
+        assert_eq!(Interval::between(Note(PitchBase::C, PitchModifier::DoubleFlat), Note(PitchBase::C, PitchModifier::Sharp)), None);
+    }
+
+    #[test]
+    fn diatonic_numbers() {
+        let note = |s: &str| s.parse::<Note>().unwrap();
+        assert_eq!(diatonic_number(note("C"), note("E")), 3);
+        assert_eq!(diatonic_number(note("G"), note("F")), 7);
+        assert_eq!(diatonic_number(note("C#"), note("Cb")), 1);
+        // C and D♭♭ sound the same but are written a second apart
+        assert_eq!(diatonic_number(note("C"), note("Dbb")), 2);
+        assert_eq!(Interval::between(note("C"), note("Dbb")), Some(Interval(IntervalQuality::Diminished, 2)));
+        assert_eq!(Interval::between(note("B#"), note("C")), Some(Interval(IntervalQuality::Diminished, 2)));
+        assert_eq!(Interval::between(note("C"), note("B#")), Some(Interval(IntervalQuality::Augmented, 7)));
+    }
+
+    #[test]
+    fn all_intervals() {
+        let all = Interval::all();
+        assert_eq!(all.len(), 12);
+        for (semitones, interval) in all.iter().enumerate() {
+            assert_eq!(interval.semitones() as usize, semitones);
+            assert_eq!(*interval, Interval::from_semitones(semitones as u8));
+            assert!(!all[semitones + 1..].contains(interval));
+        }
+        assert_eq!(all.iter().map(Interval::abbrev).collect::<Vec<_>>(), ["P1", "m2", "M2", "m3", "M3", "P4", "TT", "P5", "m6", "M6", "m7", "M7"]);
+    }
+
+    #[test]
+    fn interval_abbreviations() {
+        let intervals = [
+            (Interval::UNISON, "P1"),
+            (Interval::MINOR_SECOND, "m2"),
+            (Interval::MAJOR_SECOND, "M2"),
+            (Interval::MINOR_THIRD, "m3"),
+            (Interval::MAJOR_THIRD, "M3"),
+            (Interval::PERFECT_FOURTH, "P4"),
+            (Interval::AUGMENTED_FOURTH, "TT"),
+            (Interval::DIMINISHED_FIFTH, "TT"),
+            (Interval::PERFECT_FIFTH, "P5"),
+            (Interval::MINOR_SIXTH, "m6"),
+            (Interval::MAJOR_SIXTH, "M6"),
+            (Interval::MINOR_SEVENTH, "m7"),
+            (Interval::MAJOR_SEVENTH, "M7"),
+            (Interval::OCTAVE, "P8"),
+        ];
+        for (interval, abbrev) in intervals {
+            assert_eq!(interval.abbrev(), abbrev);
+        }
+        assert_eq!(Interval::AUGMENTED_SECOND.abbrev(), "A2");
+        assert_eq!(Interval(IntervalQuality::Diminished, 7).abbrev(), "d7");
+        assert_eq!(Interval(IntervalQuality::Diminished, 8).abbrev(), "d8");
+        // Compound intervals use their simple interval
+        assert_eq!(Interval(IntervalQuality::Major, 10).abbrev(), "M3");
+        assert_eq!(Interval(IntervalQuality::Perfect, 15).abbrev(), "P8");
+        // The long names are unchanged
+        assert_eq!(Interval::MINOR_THIRD.to_string(), "minor third");
+    }
+
+    #[test]
+    fn interval_names() {
+        let note = |s: &str| s.parse::<Note>().unwrap();
+        // The same distance is named differently depending on the letters
+        assert_eq!(Interval::name_for(note("F"), note("B")), "augmented fourth");
+        assert_eq!(Interval::name_for(note("B"), note("F")), "diminished fifth");
+        assert_eq!(Interval::name_for(note("C"), note("D#")), "augmented second");
+        assert_eq!(Interval::name_for(note("C"), note("Eb")), "minor third");
+        // Without a spelling, the plain name is used
+        assert_eq!(Interval::name_for(note("Cbb"), note("C#")), "minor third");
+        assert_eq!(format!("{}", Interval::from_semitones(6)), "augmented fourth");
+    }
+
+    #[test]
+    fn consonance() {
+        let perfect = [Interval::UNISON, Interval::PERFECT_FIFTH, Interval::OCTAVE, Interval(IntervalQuality::Perfect, 12), Interval(IntervalQuality::Perfect, 15)];
+        let imperfect = [Interval::MINOR_THIRD, Interval::MAJOR_THIRD, Interval::MINOR_SIXTH, Interval::MAJOR_SIXTH, Interval(IntervalQuality::Major, 10), Interval(IntervalQuality::Minor, 13)];
+        let dissonant = [
+            Interval::AUGMENTED_UNISON,
+            Interval::MINOR_SECOND,
+            Interval::MAJOR_SECOND,
+            Interval::AUGMENTED_SECOND,
+            Interval::PERFECT_FOURTH,
+            Interval::AUGMENTED_FOURTH,
+            Interval::DIMINISHED_FIFTH,
+            Interval(IntervalQuality::Augmented, 5),
+            Interval::MINOR_SEVENTH,
+            Interval::MAJOR_SEVENTH,
+            Interval(IntervalQuality::Diminished, 8),
+            Interval(IntervalQuality::Major, 9),
+            Interval(IntervalQuality::Perfect, 11),
+        ];
+        for interval in &perfect {
+            assert!(interval.is_perfect_consonance() && !interval.is_imperfect_consonance() && interval.is_consonant(), "{}", interval);
+        }
+        for interval in &imperfect {
+            assert!(!interval.is_perfect_consonance() && interval.is_imperfect_consonance() && interval.is_consonant(), "{}", interval);
+        }
+        for interval in &dissonant {
+            assert!(!interval.is_perfect_consonance() && !interval.is_imperfect_consonance() && !interval.is_consonant(), "{}", interval);
+        }
+        // Spelling matters, so a diminished fourth isn't a third
+        assert!(!Interval(IntervalQuality::Diminished, 4).is_consonant());
+        assert!(Interval::from_semitones(4).is_consonant());
+    }
+
+    #[test]
+    fn interval_semitones() {
+        // An augmented fourth and a diminished fifth are both six semitones
+        assert_eq!(Interval::AUGMENTED_FOURTH.semitones(), 6);
+        assert_eq!(Interval::DIMINISHED_FIFTH.semitones(), 6);
+        // A diminished seventh is nine semitones
+        assert_eq!(Interval(IntervalQuality::Diminished, 7).semitones(), 9);
+        // A diminished octave is eleven semitones
+        assert_eq!(Interval(IntervalQuality::Diminished, 8).semitones(), 11);
+        // From semitones picks the most common spelling
+        assert_eq!(Interval::from_semitones(6), Interval::AUGMENTED_FOURTH);
+        assert_eq!(Interval::from_semitones(8), Interval::MINOR_SIXTH);
+        // Enharmonic intervals are not equal
+        assert_ne!(Interval::AUGMENTED_FOURTH, Interval::DIMINISHED_FIFTH);
+        assert_eq!(format!("{}", Interval::DIMINISHED_FIFTH), "diminished fifth");
+    }
+
+    #[test]
+    fn impossible_intervals() {
+        // A quality the number can't take is measured like the nearest one it can
+        assert_eq!(Interval(IntervalQuality::Major, 5).semitones(), 7);
+        assert_eq!(Interval(IntervalQuality::Minor, 4).semitones(), 4);
+        assert_eq!(Interval(IntervalQuality::Perfect, 3).semitones(), 4);
+        assert_eq!(Interval(IntervalQuality::Major, 5).abbrev(), "P5");
+        assert_eq!(Interval(IntervalQuality::Perfect, 6).abbrev(), "M6");
+        // A diminished unison can't be smaller than nothing, and a number of 0 is a unison
+        assert_eq!(Interval(IntervalQuality::Diminished, 1).semitones(), 0);
+        assert_eq!(Interval(IntervalQuality::DoublyDiminished, 1).abbrev(), "P1");
+        assert_eq!(Interval(IntervalQuality::Perfect, 0).semitones(), 0);
+        assert_eq!(Interval(IntervalQuality::Major, 0).abbrev(), "P1");
+        assert_eq!(Interval(IntervalQuality::Perfect, 0).simple(), Interval::UNISON);
+        assert_eq!(Interval(IntervalQuality::Perfect, 0) + Interval::MAJOR_THIRD, Interval::MAJOR_THIRD);
+        // Sizes past a u8 stop at 255
+        assert_eq!(Interval(IntervalQuality::Perfect, 200).semitones(), 255);
+        assert_eq!(Interval(IntervalQuality::Perfect, 200).abbrev(), "P4");
+
+        // Nothing panics, whatever the quality and number
+        let qualities = [IntervalQuality::DoublyDiminished, IntervalQuality::Diminished, IntervalQuality::Minor, IntervalQuality::Perfect, IntervalQuality::Major, IntervalQuality::Augmented, IntervalQuality::DoublyAugmented];
+        for quality in &qualities {
+            for number in 0..=u8::MAX {
+                let interval = Interval(*quality, number);
+                let _ = (interval.semitones(), interval.abbrev(), interval.simple(), interval.inverse(), interval.to_string());
+            }
+        }
     }
 
     #[test]
     fn intervals_of_pitches() {
         // The same notes are in unison
-        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 4) - Pitch(Note(PitchBase::C, PitchModifier::Natural), 4), Interval::Unison);
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)) - Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)), Interval::UNISON);
         // C and E are a major third apart
-        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 4) - Pitch(Note(PitchBase::E, PitchModifier::Natural), 4), Interval::MajorThird);
-        // E and G are a minor third apart
-        assert_eq!(Pitch(Note(PitchBase::E, PitchModifier::Natural), 3) - Pitch(Note(PitchBase::G, PitchModifier::Natural), 4), Interval::MinorThird);
-        // C and G are a perfect fifth apart
-        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 2) - Pitch(Note(PitchBase::G, PitchModifier::Natural), 4), Interval::PerfectFifth);
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)) - Pitch(Note(PitchBase::E, PitchModifier::Natural), Octave(4)), Interval::MAJOR_THIRD);
+        // E and G are a minor third apart, compounded to a minor tenth across octaves
+        assert_eq!((Pitch(Note(PitchBase::E, PitchModifier::Natural), Octave(3)) - Pitch(Note(PitchBase::G, PitchModifier::Natural), Octave(4))).simple(), Interval::MINOR_THIRD);
+        assert_eq!(Pitch(Note(PitchBase::E, PitchModifier::Natural), Octave(3)) - Pitch(Note(PitchBase::G, PitchModifier::Natural), Octave(4)), Interval(IntervalQuality::Minor, 10));
+        // C and G are a perfect fifth apart, compounded to a perfect nineteenth across two octaves
+        assert_eq!((Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(2)) - Pitch(Note(PitchBase::G, PitchModifier::Natural), Octave(4))).simple(), Interval::PERFECT_FIFTH);
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(2)) - Pitch(Note(PitchBase::G, PitchModifier::Natural), Octave(4)), Interval(IntervalQuality::Perfect, 19));
         // C and B are a minor second apart
-        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), 4) - Pitch(Note(PitchBase::B, PitchModifier::Natural), 3), Interval::MinorSecond);
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)) - Pitch(Note(PitchBase::B, PitchModifier::Natural), Octave(3)), Interval::MINOR_SECOND);
+    }
+
+    #[test]
+    fn compound_intervals() {
+        // An octave is no longer folded into a unison
+        assert_eq!(Interval::from_semitones(12), Interval::OCTAVE);
+        assert_eq!(Interval::OCTAVE.semitones(), 12);
+        // 16 semitones is a major tenth, which simplifies to a major third
+        assert_eq!(Interval::from_semitones(16), Interval(IntervalQuality::Major, 10));
+        assert_eq!(Interval::from_semitones(16).simple(), Interval::MAJOR_THIRD);
+        assert_eq!(Interval(IntervalQuality::Major, 10).semitones(), 16);
+        // An octave simplifies to a unison, but a diminished octave stays as it is
+        assert_eq!(Interval::OCTAVE.simple(), Interval::UNISON);
+        assert_eq!(Interval(IntervalQuality::Diminished, 15).simple(), Interval(IntervalQuality::Diminished, 8));
+        // C4 and E5 are a major tenth apart
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)) - Pitch(Note(PitchBase::E, PitchModifier::Natural), Octave(5)), Interval(IntervalQuality::Major, 10));
+        assert_eq!(format!("{}", Interval(IntervalQuality::Major, 10)), "major tenth");
+        assert_eq!(format!("{}", Interval(IntervalQuality::Perfect, 22)), "perfect 22nd");
+    }
+
+    #[test]
+    fn interval_addition() {
+        assert_eq!(Interval::MAJOR_THIRD + Interval::MINOR_THIRD, Interval::PERFECT_FIFTH);
+        assert_eq!(Interval::MAJOR_THIRD + Interval::PERFECT_FIFTH, Interval::MAJOR_SEVENTH);
+        // Two major thirds are an augmented fifth, not a minor sixth
+        assert_eq!(Interval::MAJOR_THIRD + Interval::MAJOR_THIRD, Interval(IntervalQuality::Augmented, 5));
+        assert_eq!(Interval::PERFECT_FOURTH + Interval::PERFECT_FIFTH, Interval::OCTAVE);
+        assert_eq!(Interval::UNISON + Interval::MINOR_SIXTH, Interval::MINOR_SIXTH);
+        // Sums larger than an octave don't wrap
+        assert_eq!(Interval::MAJOR_SIXTH + Interval::PERFECT_FIFTH, Interval(IntervalQuality::Major, 10));
+        assert_eq!(Interval::OCTAVE + Interval::OCTAVE, Interval(IntervalQuality::Perfect, 15));
+        assert_eq!((Interval::OCTAVE + Interval::MINOR_SEVENTH).semitones(), 22);
+        assert_eq!(Interval::MAJOR_SEVENTH + Interval::MAJOR_SEVENTH, Interval(IntervalQuality::Augmented, 13));
+        // Sums that can't be spelled fall back to their size
+        let augmented_third = Interval(IntervalQuality::Augmented, 3);
+        assert_eq!(augmented_third + augmented_third, Interval::MINOR_SEVENTH);
+
+        // Sums too large for an interval are checked rather than wrapping
+        let huge = Interval(IntervalQuality::Perfect, 200);
+        assert_eq!(huge.checked_add(huge), None);
+        assert_eq!(Interval(IntervalQuality::Perfect, 15).checked_add(Interval::OCTAVE), Some(Interval(IntervalQuality::Perfect, 22)));
+        assert_eq!(Interval::from_semitones(255).checked_add(Interval::UNISON), Some(Interval::from_semitones(255)));
+        assert_eq!(Interval::from_semitones(255).checked_add(Interval::MINOR_SECOND), None);
     }
 
     #[test]
@@ -637,8 +2226,614 @@ mod tests {
         ]);
     }
 
+    fn spelling(scale: Scale) -> Vec<String> {
+        scale.notes().iter().map(|note| format!("{}", note)).collect()
+    }
+
+    #[test]
+    fn major_scale_spelling() {
+        let major = |base, modifier| spelling(Scale(Note(base, modifier), ScaleType::Ionian));
+        assert_eq!(major(PitchBase::C, PitchModifier::Natural), vec!["C", "D", "E", "F", "G", "A", "B", "C"]);
+        assert_eq!(major(PitchBase::G, PitchModifier::Natural), vec!["G", "A", "B", "C", "D", "E", "F♯", "G"]);
+        assert_eq!(major(PitchBase::D, PitchModifier::Natural), vec!["D", "E", "F♯", "G", "A", "B", "C♯", "D"]);
+        assert_eq!(major(PitchBase::A, PitchModifier::Natural), vec!["A", "B", "C♯", "D", "E", "F♯", "G♯", "A"]);
+        assert_eq!(major(PitchBase::E, PitchModifier::Natural), vec!["E", "F♯", "G♯", "A", "B", "C♯", "D♯", "E"]);
+        assert_eq!(major(PitchBase::B, PitchModifier::Natural), vec!["B", "C♯", "D♯", "E", "F♯", "G♯", "A♯", "B"]);
+        assert_eq!(major(PitchBase::F, PitchModifier::Sharp), vec!["F♯", "G♯", "A♯", "B", "C♯", "D♯", "E♯", "F♯"]);
+        assert_eq!(major(PitchBase::G, PitchModifier::Flat), vec!["G♭", "A♭", "B♭", "C♭", "D♭", "E♭", "F", "G♭"]);
+        assert_eq!(major(PitchBase::D, PitchModifier::Flat), vec!["D♭", "E♭", "F", "G♭", "A♭", "B♭", "C", "D♭"]);
+        assert_eq!(major(PitchBase::A, PitchModifier::Flat), vec!["A♭", "B♭", "C", "D♭", "E♭", "F", "G", "A♭"]);
+        assert_eq!(major(PitchBase::E, PitchModifier::Flat), vec!["E♭", "F", "G", "A♭", "B♭", "C", "D", "E♭"]);
+        assert_eq!(major(PitchBase::B, PitchModifier::Flat), vec!["B♭", "C", "D", "E♭", "F", "G", "A", "B♭"]);
+        assert_eq!(major(PitchBase::F, PitchModifier::Natural), vec!["F", "G", "A", "B♭", "C", "D", "E", "F"]);
+    }
+
+    #[test]
+    fn scale_pitches() {
+        let scale = Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::Ionian);
+        let pitches = scale.pitches("C4".parse().unwrap(), 2);
+        let text: Vec<String> = pitches.iter().map(|pitch| format!("{}", pitch)).collect();
+        assert_eq!(text, vec![
+            "C4", "D4", "E4", "F4", "G4", "A4", "B4",
+            "C5", "D5", "E5", "F5", "G5", "A5", "B5",
+            "C6",
+        ]);
+        // Scales on other roots still turn over to the next octave at C
+        let scale = Scale(Note(PitchBase::A, PitchModifier::Flat), ScaleType::Ionian);
+        let text: Vec<String> = scale.pitches("Ab3".parse().unwrap(), 1).iter().map(|pitch| format!("{}", pitch)).collect();
+        assert_eq!(text, vec!["A♭3", "B♭3", "C4", "D♭4", "E♭4", "F4", "G4", "A♭4"]);
+        // B♯ takes the octave of its letter name, even though it sounds as C5
+        let scale = Scale(Note(PitchBase::C, PitchModifier::Sharp), ScaleType::Ionian);
+        assert_eq!(format!("{}", scale.pitches("C#4".parse().unwrap(), 1)[6]), "B♯4");
+        assert_eq!(scale.pitches("C#4".parse().unwrap(), 1)[6].semitones_from_middle_c(), 12);
+        assert_eq!(scale.pitches("C#4".parse().unwrap(), 0).len(), 1);
+    }
+
+    #[test]
+    fn modes() {
+        let c_major = Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::Ionian);
+        let mode = c_major.mode(6).unwrap();
+        assert!(matches!(mode.0, Note(PitchBase::A, PitchModifier::Natural)));
+        assert_eq!(mode.1, ScaleType::Aeolian);
+        let mode = c_major.mode(2).unwrap();
+        assert!(matches!(mode.0, Note(PitchBase::D, PitchModifier::Natural)));
+        assert_eq!(mode.1, ScaleType::Dorian);
+        assert_eq!(c_major.mode(1).unwrap().1, ScaleType::Ionian);
+        // Modes of other scales keep their spelling
+        let mode = Scale(Note(PitchBase::E, PitchModifier::Flat), ScaleType::Ionian).mode(5).unwrap();
+        assert_eq!(spelling(mode.clone()), vec!["B♭", "C", "D", "E♭", "F", "G", "A♭", "B♭"]);
+        assert_eq!(mode.1, ScaleType::Mixolydian);
+        // Rotations that aren't named are custom
+        let mode = Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::HarmonicMinor).mode(2).unwrap();
+        assert_eq!(spelling(mode.clone()), vec!["D", "E♭", "F", "G", "A♭", "B", "C", "D"]);
+        assert!(matches!(mode.1, ScaleType::Custom(_)));
+        // There's no 0th or 8th mode
+        assert!(c_major.mode(0).is_none());
+        assert!(c_major.mode(8).is_none());
+    }
+
+    #[test]
+    fn flat_roots() {
+        // C♭ is below C, so its semitones from C are negative
+        let c_flat = Note(PitchBase::C, PitchModifier::Flat);
+        let scale = Scale(c_flat, ScaleType::Ionian);
+        assert_eq!(spelling(scale.clone()), vec!["C♭", "D♭", "E♭", "F♭", "G♭", "A♭", "B♭", "C♭"]);
+        assert_eq!(scale.degree_of(Note(PitchBase::B, PitchModifier::Natural)), Some(1));
+        assert_eq!(scale.degree_of(Note(PitchBase::E, PitchModifier::Natural)), Some(4));
+        assert_eq!(KeySignature::from_scale(&scale).fifths(), -7);
+        assert_eq!(Interval::between(c_flat, Note(PitchBase::E, PitchModifier::Flat)), Some(Interval::MAJOR_THIRD));
+        assert_eq!(Interval::between(Note(PitchBase::B, PitchModifier::Flat), c_flat), Some(Interval::MINOR_SECOND));
+        // Pitches on C♭ sound in the octave below
+        let pitch = Pitch(c_flat, Octave(4));
+        assert_eq!(pitch.semitones_from_middle_c(), -1);
+        assert_eq!(pitch.to_midi(), Some(59));
+        assert_eq!(format!("{}", pitch + Interval::MAJOR_SECOND), "D♭4");
+        assert_eq!(format!("{}", pitch - Interval::MINOR_SECOND), "B♭3");
+    }
+
+    #[test]
+    fn whole_tone_and_pentatonic() {
+        // Six whole steps, the last of which is spelled as a diminished third to land back on C
+        assert_eq!(spelling(Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::WholeTone)), vec!["C", "D", "E", "F♯", "G♯", "A♯", "C"]);
+        assert!(ScaleType::WholeTone.step_pattern().iter().all(|step| step.semitones() == 2));
+        // Major pentatonic has five notes
+        assert_eq!(spelling(Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::Pentatonic)), vec!["C", "D", "E", "G", "A", "C"]);
+        assert_eq!(spelling(Scale(Note(PitchBase::G, PitchModifier::Natural), ScaleType::Pentatonic)), vec!["G", "A", "B", "D", "E", "G"]);
+    }
+
+    #[test]
+    fn blues_and_symmetric_scales() {
+        let c = Note(PitchBase::C, PitchModifier::Natural);
+        // The blue note is spelled as a raised fourth
+        assert_eq!(spelling(Scale(c, ScaleType::Blues)), vec!["C", "E♭", "F", "F♯", "G", "B♭", "C"]);
+        // Octatonic scales have eight notes before the octave
+        assert_eq!(spelling(Scale(c, ScaleType::DiminishedWholeHalf)), vec!["C", "D", "E♭", "F", "G♭", "A♭", "A", "B", "C"]);
+        assert_eq!(spelling(Scale(c, ScaleType::DiminishedHalfWhole)), vec!["C", "D♭", "E♭", "E", "F♯", "G", "A", "B♭", "C"]);
+        assert_eq!(spelling(Scale(c, ScaleType::Augmented)), vec!["C", "D♯", "E", "G", "A♭", "B", "C"]);
+        assert_eq!(spelling(Scale(c, ScaleType::NeapolitanMinor)), vec!["C", "D♭", "E♭", "F", "G", "A♭", "B", "C"]);
+        assert_eq!(spelling(Scale(c, ScaleType::NeapolitanMajor)), vec!["C", "D♭", "E♭", "F", "G", "A", "B", "C"]);
+        // Every scale spans exactly an octave
+        for scale_type in NAMED_SCALE_TYPES.iter() {
+            let semitones: u8 = scale_type.step_pattern().iter().map(|step| step.semitones()).sum();
+            assert_eq!(semitones, 12, "{:?}", scale_type);
+        }
+    }
+
+    #[test]
+    fn listed_variants() {
+        // Matching on every variant means a new scale type won't compile until it's counted here, and then the count
+        // won't match until it's listed too
+        let named_variants = |scale_type: &ScaleType| match scale_type {
+            ScaleType::Ionian
+            | ScaleType::Dorian
+            | ScaleType::Phrygian
+            | ScaleType::Lydian
+            | ScaleType::Mixolydian
+            | ScaleType::Aeolian
+            | ScaleType::Locrian
+            | ScaleType::MelodicMinor
+            | ScaleType::HarmonicMinor
+            | ScaleType::WholeTone
+            | ScaleType::Pentatonic
+            | ScaleType::PhrygianDominant
+            | ScaleType::HungarianMinor
+            | ScaleType::NeapolitanMinor
+            | ScaleType::NeapolitanMajor
+            | ScaleType::Blues
+            | ScaleType::DiminishedHalfWhole
+            | ScaleType::DiminishedWholeHalf
+            | ScaleType::Augmented => 19,
+            ScaleType::Custom(_) => 0,
+        };
+        assert_eq!(named_variants(&ScaleType::Ionian), ScaleType::all().len());
+        // With no repeats, as many entries as named variants means every one of them is listed
+        for (idx, scale_type) in ScaleType::all().iter().enumerate() {
+            assert!(!ScaleType::all()[idx + 1..].contains(scale_type), "{:?} is listed twice", scale_type);
+            assert!(!scale_type.step_pattern().is_empty());
+        }
+
+        let names: Vec<String> = ScaleType::all().iter().map(|scale_type| scale_type.to_string()).collect();
+        assert_eq!(names[0], "Ionian");
+        assert_eq!(names[16], "half-whole diminished");
+        assert!(names.iter().enumerate().all(|(idx, name)| !names[idx + 1..].contains(name)));
+        assert_eq!(ScaleType::Custom(vec![Interval::PERFECT_FIFTH, Interval::PERFECT_FOURTH]).to_string(), "custom (P5 P4)");
+
+        let letters: Vec<String> = PitchBase::all().iter().map(|base| base.to_string()).collect();
+        assert_eq!(letters, ["C", "D", "E", "F", "G", "A", "B"]);
+        for (steps, base) in PitchBase::all().iter().enumerate() {
+            assert_eq!(base.steps_from_c() as usize, steps);
+        }
+    }
+
+    #[test]
+    fn every_scale_has_notes() {
+        let modifiers = [PitchModifier::DoubleFlat, PitchModifier::Flat, PitchModifier::Natural, PitchModifier::Sharp, PitchModifier::DoubleSharp];
+        let custom = ScaleType::Custom(vec![Interval::MAJOR_THIRD, Interval::MAJOR_THIRD, Interval::MAJOR_THIRD]);
+        for scale_type in ScaleType::all().iter().chain(core::iter::once(&custom)) {
+            for base in PitchBase::all() {
+                for modifier in modifiers.iter() {
+                    let root = Note(*base, *modifier);
+                    let notes = Scale(root, scale_type.clone()).notes();
+                    assert_eq!(notes.len(), scale_type.step_pattern().len() + 1, "{} {}", root, scale_type);
+                    // Even when the spelling has to fall back, the scale comes back around to its root
+                    assert_eq!(notes[notes.len() - 1], root, "{} {}", root, scale_type);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scale_lengths() {
+        let c = Note(PitchBase::C, PitchModifier::Natural);
+        // Each scale has one note per step, plus the root again at the octave
+        assert_eq!(Scale(c, ScaleType::Pentatonic).notes().len(), 6);
+        assert_eq!(Scale(c, ScaleType::WholeTone).notes().len(), 7);
+        assert_eq!(Scale(c, ScaleType::Ionian).notes().len(), 8);
+        assert_eq!(Scale(c, ScaleType::DiminishedHalfWhole).notes().len(), 9);
+        assert_eq!(Scale::from_intervals(c, vec![Interval::PERFECT_FIFTH, Interval::PERFECT_FOURTH]).notes().len(), 3);
+        // Degrees run as far as the scale does
+        let octatonic = Scale(c, ScaleType::DiminishedWholeHalf);
+        assert_eq!(format!("{}", octatonic.note_at_degree(8).unwrap()), "B");
+        assert_eq!(octatonic.note_at_degree(9), None);
+        assert_eq!(octatonic.degree_of(Note(PitchBase::A, PitchModifier::Natural)), Some(7));
+        assert_eq!(Scale(c, ScaleType::Pentatonic).degree_of(Note(PitchBase::A, PitchModifier::Natural)), Some(5));
+    }
+
+    #[test]
+    fn scale_degrees() {
+        let scale = Scale(Note(PitchBase::E, PitchModifier::Flat), ScaleType::Ionian);
+        assert_eq!(scale.degree_of(Note(PitchBase::E, PitchModifier::Flat)), Some(1));
+        assert_eq!(scale.degree_of(Note(PitchBase::B, PitchModifier::Flat)), Some(5));
+        // Notes are compared enharmonically
+        assert_eq!(scale.degree_of(Note(PitchBase::G, PitchModifier::Sharp)), Some(4));
+        assert_eq!(scale.degree_of(Note(PitchBase::E, PitchModifier::Natural)), None);
+
+        assert_eq!(format!("{}", scale.note_at_degree(4).unwrap()), "A♭");
+        assert_eq!(format!("{}", scale.note_at_degree(7).unwrap()), "D");
+        assert_eq!(scale.note_at_degree(0), None);
+        assert_eq!(scale.note_at_degree(8), None);
+        // A pentatonic scale only has five degrees
+        assert_eq!(Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::Pentatonic).note_at_degree(6), None);
+    }
+
+    #[test]
+    fn step_patterns() {
+        assert_eq!(ScaleType::Dorian.step_pattern(), &[Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_SECOND, Interval::MAJOR_SECOND]);
+        assert_eq!(ScaleType::Custom(vec![Interval::PERFECT_FIFTH, Interval::PERFECT_FOURTH]).step_pattern(), &[Interval::PERFECT_FIFTH, Interval::PERFECT_FOURTH]);
+    }
+
+    #[test]
+    fn const_step_patterns() {
+        const IONIAN: &[Interval] = ScaleType::Ionian.step_pattern();
+        const DOMINANT_SEVENTH: &[Interval] = ChordQuality::Dominant7.intervals();
+        assert_eq!(IONIAN, ScaleType::Ionian.step_pattern());
+        assert_eq!(IONIAN.len(), 7);
+        assert_eq!(DOMINANT_SEVENTH[3], Interval::MINOR_SEVENTH);
+        // The named patterns outlive the scale type they came from
+        const DORIAN: Option<&[Interval]> = ScaleType::Dorian.named_step_pattern();
+        let lydian: &'static [Interval] = {
+            let scale_type = ScaleType::Lydian;
+            scale_type.named_step_pattern().unwrap()
+        };
+        assert_eq!(DORIAN, Some(ScaleType::Dorian.step_pattern()));
+        assert_eq!(lydian, ScaleType::Lydian.step_pattern());
+        assert_eq!(ScaleType::Custom(vec![Interval::OCTAVE]).named_step_pattern(), None);
+    }
+
+    #[test]
+    fn scale_membership() {
+        let note = |s: &str| s.parse::<Note>().unwrap();
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        let d_major = Scale(note("D"), ScaleType::Ionian);
+        assert!(d_major.contains_note(note("F#")));
+        assert!(!d_major.contains_note(note("F")));
+        // Enharmonic spellings count
+        assert!(d_major.contains_note(note("Gb")));
+        assert!(!d_major.contains_note(note("B#")));
+        assert!(d_major.contains_note(note("Db")));
+        // Pitches are in the scale in any octave
+        assert!(d_major.contains_pitch(pitch("C#2")));
+        assert!(!d_major.contains_pitch(pitch("C6")));
+        let pentatonic = Scale(note("C"), ScaleType::Pentatonic);
+        assert!(pentatonic.contains_note(note("A")));
+        assert!(!pentatonic.contains_note(note("B")));
+        // Membership agrees with the scale's notes
+        for name in &["C", "C#", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B"] {
+            assert_eq!(d_major.contains_note(note(name)), d_major.notes().contains(&note(name)));
+        }
+    }
+
+    #[test]
+    fn inferred_scales() {
+        let notes = |s: &str| parse_music(s).unwrap().iter().map(|pitch| pitch.0).collect::<Vec<Note>>();
+        let ranked = infer_scale(&notes("C4 D4 E4 F4 G4 E4 A4 B4 C5 G4 E4 D4 C4"));
+        assert_eq!(ranked.len(), 12 * NAMED_SCALE_TYPES.len());
+        assert!(matches!(ranked[0], (Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::Ionian), score) if score == 1.0));
+        // The other modes of C major fit every note too, but don't end on their root
+        assert_eq!(ranked[1].1, 1.0);
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        // A Dorian melody on D ranks D Dorian first
+        let ranked = infer_scale(&notes("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4"));
+        assert!(matches!(ranked[0].0, Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian)));
+        // A melody with an F♯ doesn't fit C major
+        let ranked = infer_scale(&notes("G4 A4 B4 C5 D5 E5 F#5 G5"));
+        assert!(matches!(ranked[0].0, Scale(Note(PitchBase::G, PitchModifier::Natural), ScaleType::Ionian)));
+        let c_major = ranked.iter().find(|(scale, _)| scale.0 == Note::C && scale.1 == ScaleType::Ionian).unwrap();
+        assert_eq!(c_major.1, 7.0 / 8.0);
+    }
+
+    #[test]
+    fn pitch_class_masks() {
+        let scale = |root: &str, scale_type| Scale(root.parse().unwrap(), scale_type);
+        assert_eq!(scale("C", ScaleType::Ionian).pitch_class_mask(), 0b1010_1011_0101);
+        // A minor has the same pitch classes as C major
+        assert_eq!(scale("A", ScaleType::Aeolian).pitch_class_mask(), 0b1010_1011_0101);
+        assert_eq!(scale("C", ScaleType::WholeTone).pitch_class_mask(), 0b0101_0101_0101);
+        assert_eq!(scale("B#", ScaleType::Pentatonic).pitch_class_mask(), 0b0010_1001_0101);
+        assert_eq!(scale("C", ScaleType::DiminishedHalfWhole).pitch_class_mask().count_ones(), 8);
+    }
+
+    #[test]
+    fn custom_scales() {
+        // A custom scale with the same steps as a built-in one has the same notes
+        let dorian = ScaleType::Dorian.step_pattern().to_vec();
+        assert_eq!(spelling(Scale::from_intervals(Note(PitchBase::D, PitchModifier::Natural), dorian)), spelling(Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Dorian)));
+        // Minor pentatonic skips letter names across its minor thirds
+        let minor_pentatonic = vec![Interval::MINOR_THIRD, Interval::MAJOR_SECOND, Interval::MAJOR_SECOND, Interval::MINOR_THIRD, Interval::MAJOR_SECOND];
+        assert_eq!(spelling(Scale::from_intervals(Note(PitchBase::C, PitchModifier::Natural), minor_pentatonic)), vec!["C", "E♭", "F", "G", "B♭", "C"]);
+    }
+
+    #[test]
+    fn harmonic_minor_spelling() {
+        // The raised seventh is spelled on its own letter, an augmented second above the sixth
+        assert_eq!(spelling(Scale(Note(PitchBase::A, PitchModifier::Natural), ScaleType::HarmonicMinor)), vec!["A", "B", "C", "D", "E", "F", "G♯", "A"]);
+        assert_eq!(spelling(Scale(Note(PitchBase::E, PitchModifier::Flat), ScaleType::HarmonicMinor)), vec!["E♭", "F", "G♭", "A♭", "B♭", "C♭", "D", "E♭"]);
+    }
+
+    #[test]
+    fn midi() {
+        // Middle C is 60 and A4 is 69
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)).to_midi(), Some(60));
+        assert_eq!(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(4)).to_midi(), Some(69));
+        assert_eq!(Pitch::from_midi(69), Some(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(4))));
+        // C-1 is the lowest MIDI note and G9 the highest
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(-1)).to_midi(), Some(0));
+        assert_eq!(Pitch(Note(PitchBase::G, PitchModifier::Natural), Octave(9)).to_midi(), Some(127));
+        // Pitches outside of the MIDI range have no note number
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Flat), Octave(-1)).to_midi(), None);
+        assert_eq!(Pitch(Note(PitchBase::G, PitchModifier::Sharp), Octave(9)).to_midi(), None);
+        assert_eq!(Pitch::from_midi(128), None);
+        // Round-tripping through MIDI preserves the pitch, though not necessarily its spelling
+        for midi in 0..=127 {
+            let pitch = Pitch::from_midi(midi).unwrap();
+            assert_eq!(pitch.to_midi(), Some(midi));
+            assert_eq!(Pitch::from_midi(pitch.to_midi().unwrap()), Some(pitch));
+        }
+        let e_flat = Pitch(Note(PitchBase::E, PitchModifier::Flat), Octave(3));
+        assert_eq!(Pitch::from_midi(e_flat.to_midi().unwrap()), Some(e_flat));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn frequencies() {
+        let close = |a: f64, b: f64| (a - b).abs() < 0.01;
+        assert!(close(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(4)).frequency_standard(), 440.0));
+        assert!(close(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)).frequency_standard(), 261.63));
+        assert!(close(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(5)).frequency_standard(), 880.0));
+        // Baroque tuning puts A4 at 415 Hz
+        assert!(close(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(4)).frequency(415.0), 415.0));
+        assert!(close(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(3)).frequency(415.0), 207.5));
+
+        let (pitch, cents) = Pitch::nearest_from_frequency(440.0, STANDARD_A4_HZ).unwrap();
+        assert_eq!(pitch, Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(4)));
+        assert!(close(cents, 0.0));
+        // 445 Hz is about 19.56 cents sharp of A4
+        let (pitch, cents) = Pitch::nearest_from_frequency(445.0, STANDARD_A4_HZ).unwrap();
+        assert_eq!(pitch, Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(4)));
+        assert!(close(cents, 19.56));
+        // 255 Hz is closest to middle C, but flat
+        let (pitch, cents) = Pitch::nearest_from_frequency(255.0, STANDARD_A4_HZ).unwrap();
+        assert_eq!(pitch, Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)));
+        assert!(cents < 0.0);
+        // Frequencies too far from middle C for any pitch, or that aren't frequencies at all, have no nearest pitch
+        assert_eq!(Pitch::nearest_from_frequency(1e12, STANDARD_A4_HZ), None);
+        assert_eq!(Pitch::nearest_from_frequency(1e-12, STANDARD_A4_HZ), None);
+        assert_eq!(Pitch::nearest_from_frequency(0.0, STANDARD_A4_HZ), None);
+        assert_eq!(Pitch::nearest_from_frequency(-440.0, STANDARD_A4_HZ), None);
+    }
+
+    #[test]
+    fn helmholtz() {
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)).to_helmholtz(), "c'");
+        assert_eq!(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(0)).to_helmholtz(), "A,,");
+        assert_eq!(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(2)).to_helmholtz(), "C");
+        assert_eq!(Pitch(Note(PitchBase::B, PitchModifier::Flat), Octave(3)).to_helmholtz(), "b♭");
+        assert_eq!(Pitch(Note(PitchBase::F, PitchModifier::Sharp), Octave(6)).to_helmholtz(), "f♯'''");
+    }
+
+    #[test]
+    fn ranges() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        // Bass tops out at middle C
+        assert!(Range::BASS.contains(pitch("C4")));
+        assert!(Range::BASS.contains(pitch("B#3")));
+        assert!(!Range::BASS.contains(pitch("C#4")));
+        assert!(!Range::BASS.contains(pitch("D4")));
+        assert!(Range::BASS.contains(pitch("E2")));
+        assert!(!Range::BASS.contains(pitch("Eb2")));
+        assert!(Range::SOPRANO.contains(pitch("A5")) && !Range::SOPRANO.contains(pitch("B3")));
+        assert!(Range::ALTO.contains(pitch("G3")) && Range::TENOR.contains(pitch("G4")));
+        assert_eq!(format!("{}", Range::TENOR), "C3 to G4");
+    }
+
+    #[test]
+    fn octaves() {
+        assert_eq!(Octave::new(4), Some(Octave(4)));
+        assert_eq!(Octave::new(-1), Some(Octave::LOWEST));
+        assert_eq!(Octave::new(9), Some(Octave::HIGHEST));
+        // Octaves beyond human hearing are flagged
+        assert_eq!(Octave::new(10), None);
+        assert_eq!(Octave::new(-2), None);
+        assert_eq!(Octave::new(i8::MAX), None);
+        // Converting doesn't check the range
+        assert_eq!(Octave::from(12), Octave(12));
+        assert_eq!(i8::from(Octave(3)), 3);
+        assert_eq!(Octave(3) + 2, Octave(5));
+        assert_eq!(Octave(3) - 4, Octave(-1));
+        assert_eq!(Octave(5) - Octave(3), 2);
+        assert!(Octave(2) < Octave(3));
+        let pitch = Pitch(Note(PitchBase::E, PitchModifier::Flat), 2.into());
+        assert_eq!(format!("{}", pitch), "E♭2");
+        assert_eq!(Pitch(pitch.0, pitch.1 + 1), pitch + 12);
+    }
+
+    #[test]
+    fn distances() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        assert_eq!(pitch("E5").distance_to(&pitch("C4")), -16);
+        assert_eq!(pitch("C4").distance_to(&pitch("E5")), 16);
+        assert_eq!(pitch("B#3").distance_to(&pitch("C4")), 0);
+        // The whole range of pitches fits
+        let (lowest, highest) = (Pitch(Note::C, Octave::LOWEST), Pitch(Note::G, Octave::HIGHEST));
+        assert_eq!(lowest.distance_to(&highest), 127);
+        assert_eq!(highest.distance_to(&lowest), -127);
+    }
+
     #[test]
     fn below_middle_c() {
-        assert_eq!(Pitch::from_semitones_from_middle_c(-1), Pitch(Note(PitchBase::B, PitchModifier::Natural), 3));
+        assert_eq!(Pitch::from_semitones_from_middle_c(-1), Pitch(Note(PitchBase::B, PitchModifier::Natural), Octave(3)));
+    }
+
+    #[test]
+    fn chords() {
+        let c = Note(PitchBase::C, PitchModifier::Natural);
+        let g = Note(PitchBase::G, PitchModifier::Natural);
+        let names = |chord: Chord| chord.notes().iter().map(|note| format!("{}", note)).collect::<Vec<String>>();
+
+        // C major is C E G
+        assert_eq!(names(Chord(c, ChordQuality::Major, 0)), vec!["C", "E", "G"]);
+        // G7 is G B D F
+        assert_eq!(names(Chord(g, ChordQuality::Dominant7, 0)), vec!["G", "B", "D", "F"]);
+        // Chord tones are spelled by letter
+        assert_eq!(names(Chord(c, ChordQuality::Diminished7, 0)), vec!["C", "E♭", "G♭", "B𝄫"]);
+        assert_eq!(names(Chord(Note(PitchBase::E, PitchModifier::Flat), ChordQuality::Augmented, 0)), vec!["E♭", "G", "B"]);
+        assert_eq!(names(Chord(Note(PitchBase::F, PitchModifier::Sharp), ChordQuality::HalfDiminished7, 0)), vec!["F♯", "A", "C", "E"]);
+        // Inversions start from a different chord tone
+        assert_eq!(names(Chord(g, ChordQuality::Dominant7, 3)), vec!["F", "G", "B", "D"]);
+    }
+
+    #[test]
+    fn identify_chords() {
+        let note = |s: &str| s.parse::<Note>().unwrap();
+        let identify = |notes: &[&str]| {
+            let notes: Vec<Note> = notes.iter().map(|s| note(s)).collect();
+            Chord::identify(&notes).map(|chord| (format!("{}", chord.0), chord.1, chord.2))
+        };
+
+        // E G C is C major in first inversion
+        assert_eq!(identify(&["E", "G", "C"]), Some(("C".to_string(), ChordQuality::Major, 1)));
+        // The upper notes can come in any order, and repeat
+        assert_eq!(identify(&["E", "C", "G", "E", "C"]), Some(("C".to_string(), ChordQuality::Major, 1)));
+        assert_eq!(identify(&["F", "D", "B", "G"]), Some(("G".to_string(), ChordQuality::Dominant7, 3)));
+        assert_eq!(identify(&["A", "C", "E"]), Some(("A".to_string(), ChordQuality::Minor, 0)));
+        // Notes are compared enharmonically
+        assert_eq!(identify(&["C", "D#", "G"]), Some(("C".to_string(), ChordQuality::Minor, 0)));
+        assert_eq!(identify(&["B#", "E", "G"]), Some(("B♯".to_string(), ChordQuality::Major, 0)));
+        // Symmetrical chords are named from the bass
+        assert_eq!(identify(&["E", "C", "G#"]), Some(("E".to_string(), ChordQuality::Augmented, 0)));
+        // Anything else isn't a chord
+        assert_eq!(identify(&["C", "D", "E"]), None);
+        assert_eq!(identify(&["C", "E"]), None);
+        assert_eq!(identify(&[]), None);
+    }
+
+    #[test]
+    fn key_signatures() {
+        let note = |s: &str| s.parse::<Note>().unwrap();
+        let names = |key: KeySignature| key.accidentals().iter().map(|note| format!("{}", note)).collect::<Vec<String>>();
+
+        // C major has no sharps or flats
+        let c_major = KeySignature::from_scale(&Scale(note("C"), ScaleType::Ionian));
+        assert_eq!(c_major.fifths(), 0);
+        assert!(c_major.accidentals().is_empty());
+        // F major has one flat
+        let f_major = KeySignature::from_scale(&Scale(note("F"), ScaleType::Ionian));
+        assert_eq!(f_major.fifths(), -1);
+        assert_eq!(names(f_major), vec!["B♭"]);
+        // B major has five sharps
+        let b_major = KeySignature::from_scale(&Scale(note("B"), ScaleType::Ionian));
+        assert_eq!(b_major.fifths(), 5);
+        assert_eq!(names(b_major), vec!["F♯", "C♯", "G♯", "D♯", "A♯"]);
+        // D major has two sharps
+        assert_eq!(names(KeySignature::from_scale(&Scale(note("D"), ScaleType::Ionian))), vec!["F♯", "C♯"]);
+        // Minor keys and modes use their relative major
+        assert_eq!(KeySignature::from_scale(&Scale(note("A"), ScaleType::Aeolian)).fifths(), 0);
+        assert_eq!(KeySignature::from_scale(&Scale(note("C"), ScaleType::HarmonicMinor)).fifths(), -3);
+        assert_eq!(KeySignature::from_scale(&Scale(note("D"), ScaleType::Dorian)).fifths(), 0);
+        assert_eq!(KeySignature::from_scale(&Scale(note("F#"), ScaleType::Phrygian)).fifths(), 2);
+        assert_eq!(KeySignature::from_scale(&Scale(note("Eb"), ScaleType::Lydian)).fifths(), -2);
+        // Every note the scale alters shows up in the signature
+        let scale = Scale(note("Gb"), ScaleType::Ionian);
+        let accidentals = KeySignature::from_scale(&scale).accidentals();
+        assert_eq!(accidentals.len(), 6);
+        assert!(scale.notes().iter().filter(|note| !matches!(note.1, PitchModifier::Natural)).all(|note| accidentals.contains(note)));
+    }
+
+    #[test]
+    fn transposition() {
+        let names = |pitches: Vec<Pitch>| pitches.iter().map(|pitch| format!("{}", pitch)).collect::<Vec<String>>();
+
+        // E♭ up a minor third is G♭, not F♯
+        let melody = parse_music("Eb4 F4 G4 Ab4 Bb4").unwrap();
+        assert_eq!(names(transpose(&melody, Interval::MINOR_THIRD)), vec!["G♭4", "A♭4", "B♭4", "C♭5", "D♭5"]);
+        // Up a major third, the letter names move by two
+        let melody = parse_music("C4 D4 E4 F#4 B4").unwrap();
+        assert_eq!(names(transpose(&melody, Interval::MAJOR_THIRD)), vec!["E4", "F♯4", "G♯4", "A♯4", "D♯5"]);
+        // Compound intervals move up an extra octave
+        assert_eq!(names(transpose(&parse_music("B3").unwrap(), Interval(IntervalQuality::Minor, 10))), vec!["D5"]);
+        // The octave follows the letter name across the B to C boundary
+        let pitch = Pitch(Note(PitchBase::A, PitchModifier::Sharp), Octave(3));
+        assert_eq!(format!("{}", pitch + Interval::AUGMENTED_SECOND), "B𝄪3");
+        assert_eq!(format!("{}", pitch + Interval::MINOR_THIRD), "C♯4");
+        assert_eq!(format!("{}", Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)) - Interval::MINOR_SECOND), "B3");
+        assert_eq!(format!("{}", Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)) - Interval::AUGMENTED_FOURTH), "G♭3");
+    }
+
+    #[test]
+    fn semitone_boundaries() {
+        // Walk two octaves either side of middle C, and every pitch should land where it started
+        for semitones in -24..=24 {
+            let pitch = Pitch::from_semitones_from_middle_c(semitones);
+            assert_eq!(pitch.semitones_from_middle_c(), semitones as i16);
+            assert_eq!(pitch.1, Octave(4 + semitones.div_euclid(12)));
+        }
+        // An octave above middle C rolls over to the next octave's C
+        assert_eq!(format!("{}", Pitch::from_semitones_from_middle_c(12)), "C5");
+        assert_eq!(format!("{}", Pitch::from_semitones_from_middle_c(11)), "B4");
+        assert_eq!(format!("{}", Pitch::from_semitones_from_middle_c(24)), "C6");
+        assert_eq!(format!("{}", Pitch::from_semitones_from_middle_c(-1)), "B3");
+        assert_eq!(format!("{}", Pitch::from_semitones_from_middle_c(-12)), "C3");
+        // Semitone arithmetic crosses the boundary too
+        let c4 = Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4));
+        assert_eq!(format!("{}", c4 + 12), "C5");
+        assert_eq!(format!("{}", Pitch(Note(PitchBase::F, PitchModifier::Natural), Octave(4)) + 7), "C5");
+        assert_eq!(format!("{}", c4 - 12), "C3");
+        // Intervals that would carry a pitch too far from middle C are checked rather than wrapping
+        let g9 = Pitch(Note::G, Octave(9));
+        assert_eq!(c4.checked_add_interval(Interval::OCTAVE), Some(Pitch(Note::C, Octave(5))));
+        assert_eq!(g9.checked_add_interval(Interval::from_semitones(60)).map(|pitch| pitch.semitones_from_middle_c()), Some(127));
+        assert_eq!(g9.checked_add_interval(Interval::from_semitones(61)), None);
+        assert_eq!(c4.checked_add_interval(Interval::from_semitones(200)), None);
+        assert_eq!(c4.checked_sub_interval(Interval::from_semitones(128)), Some(Pitch::from_semitones_from_middle_c(-128)));
+        assert_eq!(c4.checked_sub_interval(Interval::from_semitones(129)), None);
+        // Octaves too far out for an i8 of semitones still count and compare in the right order
+        assert_eq!(Pitch(Note::C, Octave(15)).semitones_from_middle_c(), 132);
+        assert_eq!(Pitch(Note::E, Octave(-7)).semitones_from_middle_c(), -128);
+        assert!(Pitch(Note::C, Octave(15)) > Pitch::MIDDLE_C);
+        assert!(Pitch(Note::C, Octave(15)) > Pitch(Note::B, Octave(14)));
+        assert!(Pitch(Note::E, Octave(-7)) < Pitch(Note::C, Octave(-6)));
+        assert_ne!(Pitch(Note::C, Octave(15)), Pitch(Note::E, Octave(-7)));
+    }
+
+    #[test]
+    fn negated_directed_intervals() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+        let up = DirectedInterval::Ascending(Interval::MAJOR_THIRD);
+        assert_eq!(-up, DirectedInterval::Descending(Interval::MAJOR_THIRD));
+        assert_eq!(-&up, -up);
+        assert_eq!(-(-up), up);
+        // Negating keeps the size and spelling
+        assert_eq!((-up).interval(), up.interval());
+        assert_eq!((-up).semitones(), -up.semitones());
+        let between = DirectedInterval::between(pitch("D#4"), pitch("F5"));
+        assert_eq!(-between, DirectedInterval::between(pitch("F5"), pitch("D#4")));
+    }
+
+    #[test]
+    fn directed_intervals() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+
+        // C4 up to E4 is an ascending major third
+        let up = DirectedInterval::between(pitch("C4"), pitch("E4"));
+        assert_eq!(up, DirectedInterval::Ascending(Interval::MAJOR_THIRD));
+        assert!(up.is_ascending());
+        assert_eq!(up.semitones(), 4);
+        // E4 down to C4 is a descending major third
+        let down = DirectedInterval::between(pitch("E4"), pitch("C4"));
+        assert_eq!(down, DirectedInterval::Descending(Interval::MAJOR_THIRD));
+        assert!(!down.is_ascending());
+        assert_eq!(down.semitones(), -4);
+        assert_eq!(down.interval(), up.interval());
+        assert_eq!(format!("{}", down), "descending major third");
+        // Minor thirds across the octave boundary
+        assert_eq!(DirectedInterval::between(pitch("A4"), pitch("C5")), DirectedInterval::Ascending(Interval::MINOR_THIRD));
+        assert_eq!(DirectedInterval::between(pitch("C5"), pitch("A4")), DirectedInterval::Descending(Interval::MINOR_THIRD));
+        // Spelling follows the letter names
+        assert_eq!(DirectedInterval::between(pitch("D#4"), pitch("F4")), DirectedInterval::Ascending(Interval(IntervalQuality::Diminished, 3)));
+        assert_eq!(DirectedInterval::between(pitch("F4"), pitch("D#4")), DirectedInterval::Descending(Interval(IntervalQuality::Diminished, 3)));
+        // Octaves are kept
+        assert_eq!(DirectedInterval::between(pitch("C4"), pitch("E5")), DirectedInterval::Ascending(Interval(IntervalQuality::Major, 10)));
+        assert_eq!(DirectedInterval::between(pitch("G4"), pitch("G2")).semitones(), -24);
+        // A repeated pitch is an ascending unison
+        assert_eq!(DirectedInterval::between(pitch("G4"), pitch("G4")), DirectedInterval::Ascending(Interval::UNISON));
+    }
+
+    #[test]
+    fn motion_types() {
+        let pitch = |s: &str| s.parse::<Pitch>().unwrap();
+
+        // Fifths moving up together are parallel
+        assert_eq!(motion_type(pitch("G4"), pitch("A4"), pitch("C4"), pitch("D4")), Motion::Parallel);
+        // Thirds stay parallel even when their quality changes
+        assert_eq!(motion_type(pitch("E4"), pitch("F4"), pitch("C4"), pitch("D4")), Motion::Parallel);
+        // Moving the same way into a different interval is similar
+        assert_eq!(motion_type(pitch("E4"), pitch("A4"), pitch("C4"), pitch("D4")), Motion::Similar);
+        // Moving apart or together is contrary
+        assert_eq!(motion_type(pitch("E4"), pitch("G4"), pitch("C4"), pitch("A3")), Motion::Contrary);
+        // One voice holding its note is oblique, whichever voice it is
+        assert_eq!(motion_type(pitch("E4"), pitch("E4"), pitch("C4"), pitch("D4")), Motion::Oblique);
+        assert_eq!(motion_type(pitch("E4"), pitch("F4"), pitch("C4"), pitch("C4")), Motion::Oblique);
+        assert_eq!(motion_type(pitch("E4"), pitch("E4"), pitch("C4"), pitch("C4")), Motion::Oblique);
     }
 }
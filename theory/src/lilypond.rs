@@ -0,0 +1,71 @@
+//! Writing pitches out as LilyPond source, for engraving with `lilypond`.
+
+use crate::{Pitch, PitchModifier};
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+/// Renders the voices as a LilyPond score with one staff per voice. Every pitch is written as a quarter note in 4/4
+/// time, matching `midi::write_midi`.
+pub fn to_lilypond(voices: &[&[Pitch]]) -> String {
+    let mut ly = String::new();
+    ly.push_str("\\version \"2.18.2\"\n");
+    ly.push_str("\\score {\n");
+    ly.push_str("  <<\n");
+    for voice in voices {
+        // Voices that mostly lie below middle C get a bass clef
+        let total: i32 = voice.iter().map(|pitch| pitch.semitones_from_middle_c() as i32).sum();
+        let clef = if total < 0 { "bass" } else { "treble" };
+
+        write!(ly, "    \\new Staff {{ \\clef {} \\time 4/4", clef).unwrap();
+        for pitch in voice.iter() {
+            write!(ly, " {}4", pitch_name(pitch)).unwrap();
+        }
+        ly.push_str(" }\n");
+    }
+    ly.push_str("  >>\n");
+    ly.push_str("  \\layout { }\n");
+    ly.push_str("}\n");
+    ly
+}
+
+/// Names a pitch in LilyPond's absolute octave entry, where `c` is C3 and each `'` or `,` moves up or down an octave.
+fn pitch_name(pitch: &Pitch) -> String {
+    let modifier = match (pitch.0).1 {
+        PitchModifier::DoubleFlat => "eses",
+        PitchModifier::Flat => "es",
+        PitchModifier::Natural => "",
+        PitchModifier::Sharp => "is",
+        PitchModifier::DoubleSharp => "isis",
+    };
+    let octave = (pitch.1).0;
+    let octave = if octave >= 3 { "'".repeat((octave - 3) as usize) } else { ",".repeat((3 - octave) as usize) };
+    format!("{}{}{}", format!("{}", (pitch.0).0).to_lowercase(), modifier, octave)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn staves_and_notes() {
+        let cantus = parse_music("D4 F#4 E4 D4").unwrap();
+        let counterpoint = parse_music("D3 Bb2 C##3 D2").unwrap();
+        let ly = to_lilypond(&[&cantus, &counterpoint]);
+
+        assert!(ly.starts_with("\\version"));
+        assert_eq!(ly.matches("\\new Staff").count(), 2);
+        assert!(ly.contains("\\new Staff { \\clef treble \\time 4/4 d'4 fis'4 e'4 d'4 }"));
+        assert!(ly.contains("\\new Staff { \\clef bass \\time 4/4 d4 bes,4 cisis4 d,4 }"));
+    }
+
+    #[test]
+    fn pitch_names() {
+        let name = |s: &str| pitch_name(&s.parse().unwrap());
+        assert_eq!(name("C4"), "c'");
+        assert_eq!(name("C3"), "c");
+        assert_eq!(name("Ebb6"), "eeses'''");
+        assert_eq!(name("A0"), "a,,,");
+    }
+}
@@ -0,0 +1,117 @@
+//! Writing pitches out as standard MIDI files.
+
+use crate::Pitch;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The number of ticks in a quarter note.
+const TICKS_PER_QUARTER: u16 = 480;
+
+/// Writes the voices to a type-1 MIDI file, with a tempo track followed by one track per voice. Every pitch is played
+/// as a quarter note. Fails if a pitch is outside of the MIDI range or the tempo is zero.
+pub fn write_midi<P: AsRef<Path>>(path: P, voices: &[&[Pitch]], tempo_bpm: u32) -> io::Result<()> {
+    fs::write(path, to_midi(voices, tempo_bpm)?)
+}
+
+/// Encodes the voices as the contents of a MIDI file, like `write_midi` but without writing them anywhere.
+pub fn to_midi(voices: &[&[Pitch]], tempo_bpm: u32) -> io::Result<Vec<u8>> {
+    if tempo_bpm == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the tempo must be at least 1 bpm"));
+    }
+
+    let mut data = b"MThd".to_vec();
+    data.extend_from_slice(&6u32.to_be_bytes());
+    data.extend_from_slice(&1u16.to_be_bytes());
+    data.extend_from_slice(&(voices.len() as u16 + 1).to_be_bytes());
+    data.extend_from_slice(&TICKS_PER_QUARTER.to_be_bytes());
+
+    // The tempo is given in microseconds per quarter note
+    let tempo = (60_000_000 / tempo_bpm).min(0xFF_FFFF);
+    let mut events = vec![0x00, 0xFF, 0x51, 0x03];
+    events.extend_from_slice(&tempo.to_be_bytes()[1..]);
+    write_track(&mut data, events);
+
+    for (idx, voice) in voices.iter().enumerate() {
+        // Skip channel 10, which is reserved for percussion
+        let channel = match idx % 15 {
+            c if c >= 9 => c as u8 + 1,
+            c => c as u8,
+        };
+
+        let mut events = vec![];
+        for pitch in voice.iter() {
+            let key = pitch.to_midi().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("{} is outside of the MIDI range", pitch))
+            })?;
+            events.extend_from_slice(&[0x00, 0x90 | channel, key, 0x40]);
+            write_variable_length(&mut events, TICKS_PER_QUARTER as u32);
+            events.extend_from_slice(&[0x80 | channel, key, 0x00]);
+        }
+        write_track(&mut data, events);
+    }
+    Ok(data)
+}
+
+/// Writes a track chunk holding the events, followed by an end of track event.
+fn write_track(data: &mut Vec<u8>, mut events: Vec<u8>) {
+    events.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+    data.extend_from_slice(b"MTrk");
+    data.extend_from_slice(&(events.len() as u32).to_be_bytes());
+    data.extend_from_slice(&events);
+}
+
+/// Writes a number using seven bits per byte, most significant first, with the high bit set on all but the last.
+fn write_variable_length(data: &mut Vec<u8>, value: u32) {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    let mut value = value >> 7;
+    while value > 0 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.reverse();
+    data.extend_from_slice(&bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn variable_length() {
+        let mut data = vec![];
+        write_variable_length(&mut data, 0x40);
+        write_variable_length(&mut data, 480);
+        write_variable_length(&mut data, 0x0FFF_FFFF);
+        assert_eq!(data, vec![0x40, 0x83, 0x60, 0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn write_file() {
+        let cantus = parse_music("D4 F4 E4 D4").unwrap();
+        let counterpoint = parse_music("D3 D3 C3 D3").unwrap();
+        let path = std::env::temp_dir().join("theory_write_midi.mid");
+        write_midi(&path, &[&cantus, &counterpoint], 120).unwrap();
+
+        let data = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        // Type 1, with a tempo track and two voices, at 480 ticks per quarter
+        assert_eq!(&data[..14], &[b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 1, 0, 3, 0x01, 0xE0]);
+        // 500,000 microseconds per quarter note
+        assert_eq!(&data[14..30], &[b'M', b'T', b'r', b'k', 0, 0, 0, 11, 0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20, 0x00]);
+        // The first note of the cantus is D4 on channel 1
+        assert_eq!(&data[33..45], &[b'M', b'T', b'r', b'k', 0, 0, 0, 40, 0x00, 0x90, 62, 0x40]);
+        // The counterpoint goes on channel 2
+        assert_eq!(&data[81..93], &[b'M', b'T', b'r', b'k', 0, 0, 0, 40, 0x00, 0x91, 50, 0x40]);
+        assert_eq!(data.len(), 129);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let cantus = parse_music("D4 F4 E4 D4").unwrap();
+        assert!(to_midi(&[&cantus], 0).is_err());
+        assert!(to_midi(&[&parse_music("C0 C#0").unwrap()], 120).is_ok());
+        assert!(to_midi(&[&[Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(-2))]], 120).is_err());
+    }
+}
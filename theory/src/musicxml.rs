@@ -0,0 +1,119 @@
+//! Writing pitches out as MusicXML, for opening in notation software such as MuseScore or Finale.
+
+use crate::{Pitch, PitchModifier};
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+/// The number of quarter notes in each measure.
+const BEATS_PER_MEASURE: usize = 4;
+
+/// Renders the voices as a `score-partwise` MusicXML document with one part per voice. Every pitch is written as a
+/// quarter note in 4/4 time, matching `midi::write_midi`.
+pub fn to_musicxml(voices: &[&[Pitch]]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    xml.push_str("<!DOCTYPE score-partwise PUBLIC \"-//Recordare//DTD MusicXML 3.1 Partwise//EN\" \"http://www.musicxml.org/dtds/partwise.dtd\">\n");
+    xml.push_str("<score-partwise version=\"3.1\">\n");
+
+    xml.push_str("  <part-list>\n");
+    for idx in 0..voices.len() {
+        writeln!(xml, "    <score-part id=\"P{}\">", idx + 1).unwrap();
+        writeln!(xml, "      <part-name>Voice {}</part-name>", idx + 1).unwrap();
+        xml.push_str("    </score-part>\n");
+    }
+    xml.push_str("  </part-list>\n");
+
+    for (idx, voice) in voices.iter().enumerate() {
+        writeln!(xml, "  <part id=\"P{}\">", idx + 1).unwrap();
+        // A part needs at least one measure, even if it's empty
+        let measures: Vec<&[Pitch]> = if voice.is_empty() { vec![&[]] } else { voice.chunks(BEATS_PER_MEASURE).collect() };
+        for (number, measure) in measures.iter().enumerate() {
+            writeln!(xml, "    <measure number=\"{}\">", number + 1).unwrap();
+            if number == 0 {
+                write_attributes(&mut xml, voice);
+            }
+            for pitch in measure.iter() {
+                write_note(&mut xml, pitch);
+            }
+            xml.push_str("    </measure>\n");
+        }
+        xml.push_str("  </part>\n");
+    }
+
+    xml.push_str("</score-partwise>\n");
+    xml
+}
+
+/// Writes the divisions, key, time signature, and clef that open a part. Voices that mostly lie below middle C get a
+/// bass clef.
+fn write_attributes(xml: &mut String, voice: &[Pitch]) {
+    let total: i32 = voice.iter().map(|pitch| pitch.semitones_from_middle_c() as i32).sum();
+    let (sign, line) = if total < 0 { ("F", 4) } else { ("G", 2) };
+
+    xml.push_str("      <attributes>\n");
+    xml.push_str("        <divisions>1</divisions>\n");
+    xml.push_str("        <key>\n          <fifths>0</fifths>\n        </key>\n");
+    writeln!(xml, "        <time>\n          <beats>{}</beats>\n          <beat-type>4</beat-type>\n        </time>", BEATS_PER_MEASURE).unwrap();
+    writeln!(xml, "        <clef>\n          <sign>{}</sign>\n          <line>{}</line>\n        </clef>", sign, line).unwrap();
+    xml.push_str("      </attributes>\n");
+}
+
+fn write_note(xml: &mut String, pitch: &Pitch) {
+    let alter = match (pitch.0).1 {
+        PitchModifier::DoubleFlat => -2,
+        PitchModifier::Flat => -1,
+        PitchModifier::Natural => 0,
+        PitchModifier::Sharp => 1,
+        PitchModifier::DoubleSharp => 2,
+    };
+
+    xml.push_str("      <note>\n");
+    xml.push_str("        <pitch>\n");
+    writeln!(xml, "          <step>{}</step>", (pitch.0).0).unwrap();
+    writeln!(xml, "          <alter>{}</alter>", alter).unwrap();
+    writeln!(xml, "          <octave>{}</octave>", pitch.1).unwrap();
+    xml.push_str("        </pitch>\n");
+    xml.push_str("        <duration>1</duration>\n");
+    xml.push_str("        <type>quarter</type>\n");
+    xml.push_str("      </note>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn parts_and_notes() {
+        let cantus = parse_music("D4 F#4 E4 D4 Bbb4").unwrap();
+        let counterpoint = parse_music("D3 D3 C##3 B2 G3").unwrap();
+        let xml = to_musicxml(&[&cantus, &counterpoint]);
+
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<score-partwise version=\"3.1\">"));
+        assert!(xml.trim_end().ends_with("</score-partwise>"));
+        // One part per voice
+        assert!(xml.contains("<score-part id=\"P1\">"));
+        assert!(xml.contains("<score-part id=\"P2\">"));
+        assert_eq!(xml.matches("<part id=").count(), 2);
+        // Five quarter notes make two measures in each part
+        assert_eq!(xml.matches("<note>").count(), 10);
+        assert_eq!(xml.matches("<measure number=\"2\">").count(), 2);
+        // The counterpoint lies low enough for a bass clef
+        assert!(xml.contains("<sign>G</sign>"));
+        assert!(xml.contains("<sign>F</sign>"));
+
+        assert!(xml.contains("<step>F</step>\n          <alter>1</alter>\n          <octave>4</octave>"));
+        assert!(xml.contains("<step>B</step>\n          <alter>-2</alter>\n          <octave>4</octave>"));
+        assert!(xml.contains("<step>C</step>\n          <alter>2</alter>\n          <octave>3</octave>"));
+        assert!(xml.contains("<step>B</step>\n          <alter>0</alter>\n          <octave>2</octave>"));
+    }
+
+    #[test]
+    fn empty_voice() {
+        let xml = to_musicxml(&[&[]]);
+        assert!(xml.contains("<measure number=\"1\">"));
+        assert_eq!(xml.matches("<note>").count(), 0);
+    }
+}
@@ -0,0 +1,473 @@
+use crate::{Duration, Event, Note, NoteValue, Octave, Pitch, PitchBase, PitchModifier, Scale};
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::iter::Peekable;
+use core::str::{CharIndices, FromStr};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    UnexpectedBase(char),
+    UnexpectedModifier(char),
+    UnexpectedEnd,
+    InvalidOctave(char),
+    InvalidDuration(char),
+    UnexpectedCharacter(char),
+    /// A solfège syllable that isn't one of do, re, mi, fa, sol, la, and ti, or that names a degree the scale doesn't
+    /// have
+    UnknownSyllable,
+    /// Octave marks that move a pitch outside of `Octave::LOWEST` to `Octave::HIGHEST`
+    OctaveOutOfRange,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// An error encountered while parsing music, along with the byte offset into the input where it occurred
+pub struct ParseError {
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::UnexpectedBase(c) => write!(f, "unexpected pitch base '{}' at offset {}", c, self.offset),
+            ParseErrorKind::UnexpectedModifier(c) => write!(f, "unexpected pitch modifier '{}' at offset {}", c, self.offset),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at offset {}", self.offset),
+            ParseErrorKind::InvalidOctave(c) => write!(f, "expected an octave from 0 to 8 but found '{}' at offset {}", c, self.offset),
+            ParseErrorKind::InvalidDuration(c) => write!(f, "expected a duration of w, h, q, e, or s with at most {} dots but found '{}' at offset {}", MAX_DOTS, c, self.offset),
+            ParseErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character '{}' at offset {}", c, self.offset),
+            ParseErrorKind::UnknownSyllable => write!(f, "unknown solfège syllable at offset {}", self.offset),
+            ParseErrorKind::OctaveOutOfRange => write!(f, "octave out of range from {} to {} at offset {}", Octave::LOWEST, Octave::HIGHEST, self.offset),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// The most dots a duration may have.
+const MAX_DOTS: u8 = 3;
+
+struct Tokens<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(data: &'a str) -> Self {
+        Tokens { chars: data.char_indices().peekable(), len: data.len() }
+    }
+
+    fn next(&mut self) -> Result<(usize, char), ParseError> {
+        self.chars.next().ok_or(ParseError { offset: self.len, kind: ParseErrorKind::UnexpectedEnd })
+    }
+
+    fn next_if_eq(&mut self, expected: char) -> bool {
+        self.chars.next_if(|&(_, c)| c == expected).is_some()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|&(_, c)| c.is_ascii_whitespace()).is_some() {}
+    }
+
+    fn is_empty(&mut self) -> bool {
+        self.chars.peek().is_none()
+    }
+
+    fn expect_end(&mut self) -> Result<(), ParseError> {
+        match self.chars.next() {
+            Some((offset, c)) => Err(ParseError { offset, kind: ParseErrorKind::UnexpectedCharacter(c) }),
+            None => Ok(()),
+        }
+    }
+
+    fn note(&mut self) -> Result<Note, ParseError> {
+        let (offset, c) = self.next()?;
+        let pitch_base = match c.to_ascii_lowercase() {
+            'a' => PitchBase::A,
+            'b' => PitchBase::B,
+            'c' => PitchBase::C,
+            'd' => PitchBase::D,
+            'e' => PitchBase::E,
+            'f' => PitchBase::F,
+            'g' => PitchBase::G,
+            _ => return Err(ParseError { offset, kind: ParseErrorKind::UnexpectedBase(c) })
+        };
+
+        let pitch_modifier = match self.chars.peek().copied() {
+            Some((_, '#')) => {
+                self.chars.next();
+                if self.next_if_eq('#') {
+                    PitchModifier::DoubleSharp
+                } else {
+                    PitchModifier::Sharp
+                }
+            }
+            Some((_, 'x')) | Some((_, '𝄪')) => {
+                self.chars.next();
+                PitchModifier::DoubleSharp
+            }
+            Some((_, '♯')) => {
+                self.chars.next();
+                PitchModifier::Sharp
+            }
+            Some((_, 'b')) => {
+                self.chars.next();
+                if self.next_if_eq('b') {
+                    PitchModifier::DoubleFlat
+                } else {
+                    PitchModifier::Flat
+                }
+            }
+            Some((_, '♭')) => {
+                self.chars.next();
+                PitchModifier::Flat
+            }
+            Some((_, '𝄫')) => {
+                self.chars.next();
+                PitchModifier::DoubleFlat
+            }
+            // Helmholtz notation follows the note with primes or commas rather than an octave number
+            Some((offset, c)) if !c.is_numeric() && !c.is_whitespace() && c != '\'' && c != ',' => {
+                return Err(ParseError { offset, kind: ParseErrorKind::UnexpectedModifier(c) })
+            }
+            _ => PitchModifier::Natural,
+        };
+
+        Ok(Note(pitch_base, pitch_modifier))
+    }
+
+    fn pitch(&mut self) -> Result<Pitch, ParseError> {
+        let note = self.note()?;
+
+        let (offset, c) = self.next()?;
+        let octave = match c {
+            '0' => 0,
+            '1' => 1,
+            '2' => 2,
+            '3' => 3,
+            '4' => 4,
+            '5' => 5,
+            '6' => 6,
+            '7' => 7,
+            '8' => 8,
+            _ => return Err(ParseError { offset, kind: ParseErrorKind::InvalidOctave(c) })
+        };
+
+        Ok(Pitch(note, Octave(octave)))
+    }
+
+    /// Reads a pitch in Helmholtz notation, where lowercase letters start at C3 and go up an octave for each prime, and
+    /// uppercase letters start at C2 and go down an octave for each comma.
+    fn helmholtz_pitch(&mut self) -> Result<Pitch, ParseError> {
+        let is_lowercase = matches!(self.chars.peek(), Some((_, c)) if c.is_ascii_lowercase());
+        let note = self.note()?;
+
+        let (mut octave, mark, step) = if is_lowercase { (Octave(3), '\'', 1) } else { (Octave(2), ',', -1) };
+        while let Some((offset, _)) = self.chars.next_if(|&(_, c)| c == mark) {
+            octave = octave.0.checked_add(step).and_then(Octave::new).ok_or(ParseError { offset, kind: ParseErrorKind::OctaveOutOfRange })?;
+        }
+        Ok(Pitch(note, octave))
+    }
+
+    /// Reads a solfège syllable, followed by a `'` for each octave up or a `,` for each octave down, and gives the scale
+    /// degree it names from 1 for do to 7 for ti along with how many octaves it's moved.
+    fn solfege(&mut self) -> Result<(usize, i8), ParseError> {
+        let offset = match self.chars.peek() {
+            Some(&(offset, _)) => offset,
+            None => self.len,
+        };
+        let mut syllable = [0u8; 3];
+        let mut len = 0;
+        while let Some((offset, c)) = self.chars.next_if(|&(_, c)| c.is_ascii_alphabetic()) {
+            if len == syllable.len() {
+                return Err(ParseError { offset, kind: ParseErrorKind::UnknownSyllable });
+            }
+            syllable[len] = c.to_ascii_lowercase() as u8;
+            len += 1;
+        }
+        let degree = match &syllable[..len] {
+            b"do" => 1,
+            b"re" => 2,
+            b"mi" => 3,
+            b"fa" => 4,
+            b"sol" | b"so" => 5,
+            b"la" => 6,
+            b"ti" | b"si" => 7,
+            _ => return Err(ParseError { offset, kind: ParseErrorKind::UnknownSyllable }),
+        };
+
+        let mut octaves: i8 = 0;
+        while let Some((offset, c)) = self.chars.next_if(|&(_, c)| c == '\'' || c == ',') {
+            let step = if c == '\'' { 1 } else { -1 };
+            octaves = octaves.checked_add(step).ok_or(ParseError { offset, kind: ParseErrorKind::OctaveOutOfRange })?;
+        }
+        Ok((degree, octaves))
+    }
+
+    /// Reads an optional duration suffix such as `:q` or `:h.`, which defaults to a quarter note when there isn't one.
+    fn duration(&mut self) -> Result<Duration, ParseError> {
+        if !self.next_if_eq(':') {
+            return Ok(Duration::QUARTER);
+        }
+
+        let (offset, c) = self.next()?;
+        let value = match c {
+            'w' => NoteValue::Whole,
+            'h' => NoteValue::Half,
+            'q' => NoteValue::Quarter,
+            'e' => NoteValue::Eighth,
+            's' => NoteValue::Sixteenth,
+            _ => return Err(ParseError { offset, kind: ParseErrorKind::InvalidDuration(c) })
+        };
+
+        let mut duration = Duration::new(value);
+        while let Some((offset, _)) = self.chars.next_if(|&(_, c)| c == '.') {
+            if duration.dots == MAX_DOTS {
+                return Err(ParseError { offset, kind: ParseErrorKind::InvalidDuration('.') });
+            }
+            duration = duration.dotted();
+        }
+        Ok(duration)
+    }
+
+    fn event(&mut self) -> Result<Event, ParseError> {
+        if self.next_if_eq('r') || self.next_if_eq('R') {
+            return Ok(Event::Rest(self.duration()?));
+        }
+        let pitch = self.pitch()?;
+        Ok(Event::Note(pitch, self.duration()?))
+    }
+}
+
+impl FromStr for Note {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens::new(s);
+        let note = tokens.note()?;
+        tokens.expect_end()?;
+        Ok(note)
+    }
+}
+
+impl FromStr for Pitch {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens::new(s);
+        let pitch = tokens.pitch()?;
+        tokens.expect_end()?;
+        Ok(pitch)
+    }
+}
+
+impl Pitch {
+    /// Parses a pitch in Helmholtz notation, such as `c'` for middle C or `A,,` for A0. Accidentals are written as
+    /// they would be for scientific pitch notation, so `bb` is the B♭ below middle C.
+    pub fn from_helmholtz(s: &str) -> Result<Self, ParseError> {
+        let mut tokens = Tokens::new(s);
+        let pitch = tokens.helmholtz_pitch()?;
+        tokens.expect_end()?;
+        Ok(pitch)
+    }
+}
+
+/// Parses a sequence of pitches separated by optional whitespace, such as `D4 F4 E4 D4`.
+pub fn parse_music(data: &str) -> Result<Vec<Pitch>, ParseError> {
+    let mut tokens = Tokens::new(data);
+    let mut result = vec![];
+
+    loop {
+        tokens.skip_whitespace();
+        if tokens.is_empty() {
+            break;
+        }
+        result.push(tokens.pitch()?);
+    }
+    Ok(result)
+}
+
+/// Parses a sequence of movable-do solfège syllables separated by whitespace, such as `do re mi fa, sol,`, into pitches
+/// of the scale. Do is the scale's root in the octave of middle C, and each following syllable is a degree above it, so
+/// `do re mi` in D major is D4 E4 F♯4. A `'` after a syllable moves it up an octave and a `,` moves it down.
+/// `sol` or `so`, and `ti` or `si`, are both accepted.
+pub fn parse_solfege(data: &str, scale: &Scale) -> Result<Vec<Pitch>, ParseError> {
+    let degrees = scale.pitches(Pitch(scale.0, Octave(4)), 1);
+    let mut tokens = Tokens::new(data);
+    let mut result = vec![];
+
+    loop {
+        tokens.skip_whitespace();
+        let offset = match tokens.chars.peek() {
+            Some(&(offset, _)) => offset,
+            None => break,
+        };
+        let (degree, octaves) = tokens.solfege()?;
+        // The last pitch is the root an octave up, which isn't a degree of its own
+        if degree >= degrees.len() {
+            return Err(ParseError { offset, kind: ParseErrorKind::UnknownSyllable });
+        }
+        let pitch = degrees[degree - 1];
+        let octave = (pitch.1).0.checked_add(octaves).and_then(Octave::new).ok_or(ParseError { offset, kind: ParseErrorKind::OctaveOutOfRange })?;
+        result.push(Pitch(pitch.0, octave));
+
+        if !tokens.is_empty() && !matches!(tokens.chars.peek(), Some((_, c)) if c.is_ascii_whitespace()) {
+            tokens.expect_end()?;
+        }
+    }
+    Ok(result)
+}
+
+/// Parses a sequence of notes and rests separated by optional whitespace, such as `D4:h F4 r:q E4:h.`. Each note or
+/// rest may be followed by a colon and a duration: `w` for whole, `h` for half, `q` for quarter, `e` for eighth, or `s`
+/// for sixteenth, with a `.` for each dot. Without one, the duration is a quarter note, so anything `parse_music`
+/// reads can be read here too.
+pub fn parse_events(data: &str) -> Result<Vec<Event>, ParseError> {
+    let mut tokens = Tokens::new(data);
+    let mut result = vec![];
+
+    loop {
+        tokens.skip_whitespace();
+        if tokens.is_empty() {
+            break;
+        }
+        result.push(tokens.event()?);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "std"))]
+    use alloc::{format, string::String};
+    use crate::ScaleType;
+
+    #[test]
+    fn parse_pitches() {
+        assert_eq!(parse_music("C4 D#4\nBb3"), Ok(vec![
+            Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4)),
+            Pitch(Note(PitchBase::D, PitchModifier::Sharp), Octave(4)),
+            Pitch(Note(PitchBase::B, PitchModifier::Flat), Octave(3)),
+        ]));
+        assert_eq!(parse_music("  "), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse_music("C4 H4"), Err(ParseError { offset: 3, kind: ParseErrorKind::UnexpectedBase('H') }));
+        assert_eq!(parse_music("C4 D?4"), Err(ParseError { offset: 4, kind: ParseErrorKind::UnexpectedModifier('?') }));
+        assert_eq!(parse_music("C4 D#"), Err(ParseError { offset: 5, kind: ParseErrorKind::UnexpectedEnd }));
+        assert_eq!(parse_music("C9"), Err(ParseError { offset: 1, kind: ParseErrorKind::InvalidOctave('9') }));
+    }
+
+    #[test]
+    fn parse_double_modifiers() {
+        assert_eq!(parse_music("C##4 Cx4 Dbb4 Bb3"), Ok(vec![
+            Pitch(Note(PitchBase::C, PitchModifier::DoubleSharp), Octave(4)),
+            Pitch(Note(PitchBase::C, PitchModifier::DoubleSharp), Octave(4)),
+            Pitch(Note(PitchBase::D, PitchModifier::DoubleFlat), Octave(4)),
+            Pitch(Note(PitchBase::B, PitchModifier::Flat), Octave(3)),
+        ]));
+        assert_eq!(parse_music("bbb4"), Ok(vec![Pitch(Note(PitchBase::B, PitchModifier::DoubleFlat), Octave(4))]));
+        assert_eq!(parse_music("bbb"), Err(ParseError { offset: 3, kind: ParseErrorKind::UnexpectedEnd }));
+        assert_eq!(parse_music("Cbbb4"), Err(ParseError { offset: 3, kind: ParseErrorKind::InvalidOctave('b') }));
+        assert_eq!(parse_music("C###4"), Err(ParseError { offset: 3, kind: ParseErrorKind::InvalidOctave('#') }));
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let pitches = vec![
+            Pitch(Note(PitchBase::C, PitchModifier::DoubleFlat), Octave(2)),
+            Pitch(Note(PitchBase::E, PitchModifier::Flat), Octave(3)),
+            Pitch(Note(PitchBase::G, PitchModifier::Natural), Octave(4)),
+            Pitch(Note(PitchBase::F, PitchModifier::Sharp), Octave(5)),
+            Pitch(Note(PitchBase::A, PitchModifier::DoubleSharp), Octave(6)),
+        ];
+        let text: Vec<String> = pitches.iter().map(|pitch| format!("{}", pitch)).collect();
+        let parsed = parse_music(&text.join(" ")).unwrap();
+        assert_eq!(parsed, pitches);
+        // Pitches compare enharmonically, so check the spelling survived too
+        let reparsed: Vec<String> = parsed.iter().map(|pitch| format!("{}", pitch)).collect();
+        assert_eq!(reparsed, text);
+    }
+
+    #[test]
+    fn parse_rhythm() {
+        let d4 = Pitch(Note(PitchBase::D, PitchModifier::Natural), Octave(4));
+        let f4 = Pitch(Note(PitchBase::F, PitchModifier::Natural), Octave(4));
+        assert_eq!(parse_events("D4:h F4 r:e. Bb3:q.."), Ok(vec![
+            Event::Note(d4, Duration::HALF),
+            Event::Note(f4, Duration::QUARTER),
+            Event::Rest(Duration::EIGHTH.dotted()),
+            Event::Note(Pitch(Note(PitchBase::B, PitchModifier::Flat), Octave(3)), Duration::QUARTER.dotted().dotted()),
+        ]));
+        // Without a duration, notes and rests are quarters
+        assert_eq!(parse_events("D4 R"), Ok(vec![Event::Note(d4, Duration::QUARTER), Event::Rest(Duration::QUARTER)]));
+        assert_eq!(parse_events("D4:w\nF4:s"), Ok(vec![Event::Note(d4, Duration::WHOLE), Event::Note(f4, Duration::SIXTEENTH)]));
+        // A bad duration is reported separately from a bad pitch
+        assert_eq!(parse_events("D4:x"), Err(ParseError { offset: 3, kind: ParseErrorKind::InvalidDuration('x') }));
+        assert_eq!(parse_events("D4:h..."), Ok(vec![Event::Note(d4, Duration::HALF.dotted().dotted().dotted())]));
+        assert_eq!(parse_events("D4:h...."), Err(ParseError { offset: 7, kind: ParseErrorKind::InvalidDuration('.') }));
+        assert_eq!(parse_events("H4:q"), Err(ParseError { offset: 0, kind: ParseErrorKind::UnexpectedBase('H') }));
+        assert_eq!(parse_events("D4:"), Err(ParseError { offset: 3, kind: ParseErrorKind::UnexpectedEnd }));
+        assert_eq!(parse_events("D4:q:h"), Err(ParseError { offset: 4, kind: ParseErrorKind::UnexpectedBase(':') }));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("C#".parse::<Note>(), Ok(Note(PitchBase::C, PitchModifier::Sharp)));
+        assert_eq!("Ab3".parse::<Pitch>(), Ok(Pitch(Note(PitchBase::A, PitchModifier::Flat), Octave(3))));
+        assert_eq!("C#4".parse::<Note>(), Err(ParseError { offset: 2, kind: ParseErrorKind::UnexpectedCharacter('4') }));
+        assert_eq!("Ab3 C4".parse::<Pitch>(), Err(ParseError { offset: 3, kind: ParseErrorKind::UnexpectedCharacter(' ') }));
+        assert_eq!("Ab".parse::<Pitch>(), Err(ParseError { offset: 2, kind: ParseErrorKind::UnexpectedEnd }));
+    }
+
+    #[test]
+    fn parse_solfege_syllables() {
+        let d_major = Scale(Note(PitchBase::D, PitchModifier::Natural), ScaleType::Ionian);
+        let pitches = |s: &str| parse_music(s).unwrap();
+        assert_eq!(parse_solfege("do re mi", &d_major), Ok(pitches("D4 E4 F#4")));
+        // Syllables go on upward from do, and octave marks move them
+        assert_eq!(parse_solfege("Sol la ti do'", &d_major), Ok(pitches("A4 B4 C#5 D5")));
+        assert_eq!(parse_solfege("do ti, so,, si", &d_major), Ok(pitches("D4 C#4 A2 C#5")));
+        assert_eq!(parse_solfege("  ", &d_major), Ok(vec![]));
+
+        let a_minor = Scale(Note(PitchBase::A, PitchModifier::Natural), ScaleType::Aeolian);
+        assert_eq!(parse_solfege("do mi sol", &a_minor), Ok(pitches("A4 C5 E5")));
+
+        assert_eq!(parse_solfege("do ra mi", &d_major), Err(ParseError { offset: 3, kind: ParseErrorKind::UnknownSyllable }));
+        assert_eq!(parse_solfege("do sola", &d_major), Err(ParseError { offset: 6, kind: ParseErrorKind::UnknownSyllable }));
+        assert_eq!(parse_solfege("do re4", &d_major), Err(ParseError { offset: 5, kind: ParseErrorKind::UnexpectedCharacter('4') }));
+        // Octave marks can't go past the octaves that can be heard, however many there are
+        assert_eq!(parse_solfege("do do''''' do''''''", &d_major), Err(ParseError { offset: 11, kind: ParseErrorKind::OctaveOutOfRange }));
+        assert_eq!(parse_solfege("do,,,,,,", &d_major), Err(ParseError { offset: 0, kind: ParseErrorKind::OctaveOutOfRange }));
+        assert_eq!(parse_solfege(&format!("mi{}", "'".repeat(200)), &d_major), Err(ParseError { offset: 129, kind: ParseErrorKind::OctaveOutOfRange }));
+        // A pentatonic scale has no seventh degree
+        let pentatonic = Scale(Note(PitchBase::C, PitchModifier::Natural), ScaleType::Pentatonic);
+        assert_eq!(parse_solfege("do ti", &pentatonic), Err(ParseError { offset: 3, kind: ParseErrorKind::UnknownSyllable }));
+    }
+
+    #[test]
+    fn parse_helmholtz() {
+        assert_eq!(Pitch::from_helmholtz("c'"), Ok(Pitch(Note(PitchBase::C, PitchModifier::Natural), Octave(4))));
+        assert_eq!(Pitch::from_helmholtz("A,,"), Ok(Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(0))));
+        assert_eq!(Pitch::from_helmholtz("f#''"), Ok(Pitch(Note(PitchBase::F, PitchModifier::Sharp), Octave(5))));
+        assert_eq!(Pitch::from_helmholtz("bb"), Ok(Pitch(Note(PitchBase::B, PitchModifier::Flat), Octave(3))));
+        assert_eq!(Pitch::from_helmholtz("E♭"), Ok(Pitch(Note(PitchBase::E, PitchModifier::Flat), Octave(2))));
+        // Primes only go with lowercase letters, and commas with uppercase
+        assert_eq!(Pitch::from_helmholtz("c,"), Err(ParseError { offset: 1, kind: ParseErrorKind::UnexpectedCharacter(',') }));
+        // Octave marks can't go past the octaves that can be heard
+        assert_eq!(Pitch::from_helmholtz("c''''''"), Ok(Pitch(Note::C, Octave::HIGHEST)));
+        assert_eq!(Pitch::from_helmholtz("c'''''''"), Err(ParseError { offset: 7, kind: ParseErrorKind::OctaveOutOfRange }));
+        assert_eq!(Pitch::from_helmholtz("C,,,"), Ok(Pitch(Note::C, Octave::LOWEST)));
+        assert!(Pitch::from_helmholtz(&format!("C{}", ",".repeat(200))).is_err());
+        assert_eq!(Pitch::from_helmholtz("C'"), Err(ParseError { offset: 1, kind: ParseErrorKind::UnexpectedCharacter('\'') }));
+        assert_eq!(Pitch::from_helmholtz("c4"), Err(ParseError { offset: 1, kind: ParseErrorKind::UnexpectedCharacter('4') }));
+        // Scientific pitch notation still rejects them
+        assert_eq!("c'".parse::<Pitch>(), Err(ParseError { offset: 1, kind: ParseErrorKind::InvalidOctave('\'') }));
+        // Writing a pitch out and reading it back gives the same spelling
+        for text in &["C,,", "B♭,", "C", "c", "c♯'", "g𝄫''"] {
+            assert_eq!(Pitch::from_helmholtz(text).unwrap().to_helmholtz(), *text);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Rhythm: how long notes and rests last.
+
+use crate::Pitch;
+use core::convert::TryFrom;
+
+/// The undotted length of a note, named by its fraction of a whole note.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoteValue {
+    Whole,
+    Half,
+    Quarter,
+    Eighth,
+    Sixteenth,
+}
+
+impl NoteValue {
+    /// The number of times a whole note must be halved to get this value.
+    fn halvings(&self) -> u32 {
+        match self {
+            NoteValue::Whole => 0,
+            NoteValue::Half => 1,
+            NoteValue::Quarter => 2,
+            NoteValue::Eighth => 3,
+            NoteValue::Sixteenth => 4,
+        }
+    }
+}
+
+/// A tuplet ratio, fitting the first number of notes into the time normally taken by the second. A triplet is
+/// `Tuplet(3, 2)`.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tuplet(pub u8, pub u8);
+
+/// How long a note or rest lasts.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Duration {
+    pub value: NoteValue,
+    /// Each dot adds half again of the previous length, so a dotted half lasts three quarters.
+    pub dots: u8,
+    pub tuplet: Option<Tuplet>,
+}
+
+impl Duration {
+    pub const WHOLE: Duration = Duration::new(NoteValue::Whole);
+    pub const HALF: Duration = Duration::new(NoteValue::Half);
+    pub const QUARTER: Duration = Duration::new(NoteValue::Quarter);
+    pub const EIGHTH: Duration = Duration::new(NoteValue::Eighth);
+    pub const SIXTEENTH: Duration = Duration::new(NoteValue::Sixteenth);
+
+    /// An undotted duration outside of any tuplet.
+    pub const fn new(value: NoteValue) -> Self {
+        Duration { value, dots: 0, tuplet: None }
+    }
+
+    /// Adds a dot to the duration.
+    pub fn dotted(self) -> Self {
+        Duration { dots: self.dots.saturating_add(1), ..self }
+    }
+
+    /// Puts the duration inside a tuplet.
+    pub fn in_tuplet(self, tuplet: Tuplet) -> Self {
+        Duration { tuplet: Some(tuplet), ..self }
+    }
+
+    /// Gets the length of the duration in ticks, given how many ticks make up a quarter note. Lengths that don't come
+    /// out to a whole number of ticks are rounded down. Gives `None` for a tuplet of no notes, or for a duration too
+    /// long, or with too many dots, to count in ticks.
+    pub fn to_ticks(&self, ticks_per_quarter: u32) -> Option<u32> {
+        // A duration with n dots lasts (2^(n + 1) - 1) / 2^n times its undotted value
+        let mut numerator = (4 * ticks_per_quarter as u64).checked_mul(1u64.checked_shl(self.dots as u32 + 1)? - 1)?;
+        let mut denominator = 1u64.checked_shl(self.value.halvings() + self.dots as u32)?;
+        if let Some(Tuplet(notes, in_time_of)) = self.tuplet {
+            numerator = numerator.checked_mul(in_time_of as u64)?;
+            denominator = denominator.checked_mul(notes as u64)?;
+        }
+        u32::try_from(numerator.checked_div(denominator)?).ok()
+    }
+}
+
+/// Something that happens in a voice: either a pitch sounding or a rest, each for some duration.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    Note(Pitch, Duration),
+    Rest(Duration),
+}
+
+impl Event {
+    pub fn duration(&self) -> Duration {
+        match self {
+            Event::Note(_, duration) | Event::Rest(duration) => *duration,
+        }
+    }
+
+    /// Gets the pitch that sounds, or `None` for a rest.
+    pub fn pitch(&self) -> Option<Pitch> {
+        match self {
+            Event::Note(pitch, _) => Some(*pitch),
+            Event::Rest(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn ticks() {
+        assert_eq!(Duration::WHOLE.to_ticks(480), Some(1920));
+        assert_eq!(Duration::QUARTER.to_ticks(480), Some(480));
+        assert_eq!(Duration::SIXTEENTH.to_ticks(480), Some(120));
+        // Three eighth notes of a triplet fill a quarter note
+        let triplet = Duration::EIGHTH.in_tuplet(Tuplet(3, 2));
+        assert_eq!(triplet.to_ticks(480), Some(160));
+        assert_eq!(triplet.to_ticks(480).map(|ticks| 3 * ticks), Duration::QUARTER.to_ticks(480));
+        // Lengths that don't divide evenly are rounded down
+        assert_eq!(Duration::QUARTER.in_tuplet(Tuplet(5, 4)).to_ticks(1), Some(0));
+        // A tuplet of no notes has no length, and some lengths don't fit in a u32
+        assert_eq!(Duration::QUARTER.in_tuplet(Tuplet(0, 2)).to_ticks(480), None);
+        assert_eq!(Duration::WHOLE.to_ticks(u32::MAX), None);
+        assert_eq!(Duration::WHOLE.in_tuplet(Tuplet(1, 255)).to_ticks(u32::MAX / 4), None);
+    }
+
+    #[test]
+    fn dotted_durations() {
+        let ticks = |duration: Duration| duration.to_ticks(480).unwrap();
+        // A dotted quarter lasts a quarter and an eighth
+        let dotted_quarter = Duration::QUARTER.dotted();
+        assert_eq!(dotted_quarter, Duration { value: NoteValue::Quarter, dots: 1, tuplet: None });
+        assert_eq!(ticks(dotted_quarter), ticks(Duration::QUARTER) + ticks(Duration::EIGHTH));
+        // A double-dotted half lasts a half, a quarter, and an eighth
+        let double_dotted_half = Duration::HALF.dotted().dotted();
+        assert_eq!(ticks(double_dotted_half), 960 + 480 + 240);
+        // Two dotted quarters fill a dotted half
+        assert_eq!(2 * ticks(dotted_quarter), ticks(Duration::HALF.dotted()));
+        // Too many dots to count doesn't overflow
+        let dots = |dots: u8| Duration { dots, ..Duration::QUARTER };
+        assert_eq!(dots(60).to_ticks(480), None);
+        assert_eq!(dots(u8::MAX).to_ticks(480), None);
+        assert_eq!(dots(u8::MAX).dotted(), dots(u8::MAX));
+    }
+
+    #[test]
+    fn events() {
+        let pitch = Pitch(Note(PitchBase::D, PitchModifier::Natural), Octave(4));
+        let note = Event::Note(pitch, Duration::HALF);
+        assert_eq!(note.pitch(), Some(pitch));
+        assert_eq!(note.duration(), Duration::HALF);
+        let rest = Event::Rest(Duration::EIGHTH.dotted());
+        assert_eq!(rest.pitch(), None);
+        assert_eq!(rest.duration().to_ticks(480), Some(360));
+    }
+}
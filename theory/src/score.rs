@@ -0,0 +1,101 @@
+//! Scores: several voices of notes and rests written in a key and time signature.
+
+use crate::{Duration, Event, Pitch, Scale};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// One line of music, such as the cantus firmus or a line of counterpoint.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Voice(pub Vec<Event>);
+
+impl Voice {
+    /// Makes a voice that plays each pitch in turn for the same duration, such as a line from the solver.
+    pub fn from_pitches(pitches: &[Pitch], duration: Duration) -> Self {
+        Voice(pitches.iter().map(|pitch| Event::Note(*pitch, duration)).collect())
+    }
+
+    /// Gets the pitches the voice plays in order, skipping rests.
+    pub fn pitches(&self) -> Vec<Pitch> {
+        self.0.iter().filter_map(Event::pitch).collect()
+    }
+}
+
+/// A piece of music made up of voices that all share a key and time signature.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Score {
+    /// The number of beats in a measure and the note value that gets a beat, so `(3, 4)` is three-four time.
+    pub time_signature: (u8, u8),
+    pub key: Scale,
+    pub voices: Vec<Voice>,
+}
+
+impl Score {
+    /// Makes a score with no voices yet.
+    pub fn new(time_signature: (u8, u8), key: Scale) -> Self {
+        Score { time_signature, key, voices: Vec::new() }
+    }
+
+    /// Adds a voice below the ones already in the score.
+    pub fn add_voice(&mut self, voice: Voice) {
+        self.voices.push(voice);
+    }
+
+    /// Whether the beat at `index` is a strong beat in the score's time signature, as judged by `is_strong_beat`.
+    pub fn is_strong_beat(&self, index: usize) -> bool {
+        is_strong_beat(index, self.time_signature)
+    }
+}
+
+/// Whether the beat at `index`, counting beats from the start of the piece, is a strong beat in the time signature, so
+/// that dissonances can be kept to the weak beats. The first beat of each measure is always strong. In four-four the
+/// third beat is strong too, and in compound time such as six-eight, so is the first beat of each group of three.
+pub fn is_strong_beat(index: usize, time_signature: (u8, u8)) -> bool {
+    let beats = time_signature.0.max(1) as usize;
+    let beat = index % beats;
+    match beats {
+        4 => beat.is_multiple_of(2),
+        6 | 9 | 12 => beat.is_multiple_of(3),
+        _ => beat == 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn construction() {
+        let mut score = Score::new((4, 4), Scale(Note::D, ScaleType::Dorian));
+        assert!(score.voices.is_empty());
+
+        let cantus = parse_music("D4 F4 E4 D4").unwrap();
+        score.add_voice(Voice::from_pitches(&cantus, Duration::WHOLE));
+        score.add_voice(Voice(parse_events("r:h A3:h D4 C4 D4:w").unwrap()));
+
+        assert_eq!(score.time_signature, (4, 4));
+        assert_eq!(score.voices.len(), 2);
+        assert_eq!(score.voices[0].0[1], Event::Note(cantus[1], Duration::WHOLE));
+        assert_eq!(score.voices[0].pitches(), cantus);
+        // Rests are skipped when getting the pitches
+        assert_eq!(score.voices[1].0.len(), 5);
+        assert_eq!(score.voices[1].pitches(), parse_music("A3 D4 C4 D4").unwrap());
+    }
+
+    #[test]
+    fn strong_beats() {
+        let strong = |time_signature, count| (0..count).filter(|idx| is_strong_beat(*idx, time_signature)).collect::<Vec<usize>>();
+        // The first and third beats of each measure in four-four
+        assert_eq!(strong((4, 4), 8), [0, 2, 4, 6]);
+        // Only the first beat in three-four
+        assert_eq!(strong((3, 4), 9), [0, 3, 6]);
+        assert_eq!(strong((2, 2), 4), [0, 2]);
+        assert_eq!(strong((6, 8), 12), [0, 3, 6, 9]);
+
+        let score = Score::new((3, 4), Scale(Note::C, ScaleType::Ionian));
+        assert!(score.is_strong_beat(3));
+        assert!(!score.is_strong_beat(4));
+    }
+}
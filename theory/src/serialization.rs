@@ -0,0 +1,82 @@
+//! Serde support for the theory types. Notes and pitches are written in the same ASCII notation `parse_music`
+//! accepts, such as `"C#4"`, and are read back through their `FromStr` implementations.
+
+use crate::{Note, Pitch, PitchModifier};
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+fn ascii_note(note: &Note) -> String {
+    let modifier = match note.1 {
+        PitchModifier::DoubleFlat => "bb",
+        PitchModifier::Flat => "b",
+        PitchModifier::Natural => "",
+        PitchModifier::Sharp => "#",
+        PitchModifier::DoubleSharp => "##",
+    };
+    format!("{}{}", note.0, modifier)
+}
+
+impl Serialize for Note {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&ascii_note(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Note {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Pitch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{}{}", ascii_note(&self.0), self.1))
+    }
+}
+
+impl<'de> Deserialize<'de> for Pitch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn pitches() {
+        let cantus = vec![
+            Pitch(Note(PitchBase::D, PitchModifier::Natural), Octave(4)),
+            Pitch(Note(PitchBase::F, PitchModifier::Sharp), Octave(4)),
+            Pitch(Note(PitchBase::B, PitchModifier::DoubleFlat), Octave(3)),
+        ];
+        let json = serde_json::to_string(&cantus).unwrap();
+        assert_eq!(json, r#"["D4","F#4","Bbb3"]"#);
+        let parsed: Vec<Pitch> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, cantus);
+        assert_eq!(format!("{}", parsed[2]), "B𝄫3");
+    }
+
+    #[test]
+    fn scales_and_intervals() {
+        let scale = Scale(Note(PitchBase::E, PitchModifier::Flat), ScaleType::Dorian);
+        let json = serde_json::to_string(&scale).unwrap();
+        assert_eq!(json, r#"["Eb","Dorian"]"#);
+        let parsed: Scale = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.notes(), scale.notes());
+
+        let json = serde_json::to_string(&Interval::MINOR_SIXTH).unwrap();
+        assert_eq!(serde_json::from_str::<Interval>(&json).unwrap(), Interval::MINOR_SIXTH);
+    }
+
+    #[test]
+    fn invalid_pitches() {
+        assert!(serde_json::from_str::<Pitch>(r#""H4""#).is_err());
+        assert!(serde_json::from_str::<Note>(r#""C4""#).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Drawing pitches on a rough text staff, for a quick look at a line in the terminal.
+
+use crate::Pitch;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// The staff positions of the bottom and top lines of the treble staff, E4 and F5, counted in letter names from middle
+/// C.
+const BOTTOM_LINE: i32 = 2;
+const TOP_LINE: i32 = 10;
+
+/// Draws the voices on a treble staff as text, one column per note with the voices' notes at the same index in the
+/// same column. Noteheads are drawn as `o`, and ledger lines are added for notes above or below the staff. Notes are
+/// placed by letter name and octave, so accidentals aren't shown.
+pub fn render_staff(voices: &[&[Pitch]]) -> String {
+    let len = voices.iter().map(|voice| voice.len()).max().unwrap_or(0);
+    let columns: Vec<Vec<i32>> = (0..len).map(|idx| voices.iter().filter_map(|voice| voice.get(idx)).map(staff_position).collect()).collect();
+
+    let top = columns.iter().flatten().copied().fold(TOP_LINE, i32::max);
+    let bottom = columns.iter().flatten().copied().fold(BOTTOM_LINE, i32::min);
+
+    let mut staff = String::new();
+    for position in (bottom..=top).rev() {
+        let mut row = String::new();
+        for column in &columns {
+            // Ledger lines reach out from the staff as far as the furthest note
+            let is_line = position % 2 == 0
+                && ((BOTTOM_LINE..=TOP_LINE).contains(&position)
+                    || (position > TOP_LINE && column.iter().any(|note| *note >= position))
+                    || (position < BOTTOM_LINE && column.iter().any(|note| *note <= position)));
+            let fill = if is_line { '-' } else { ' ' };
+            row.push(fill);
+            row.push(if column.contains(&position) { 'o' } else { fill });
+            row.push(fill);
+        }
+        staff.push_str(row.trim_end());
+        staff.push('\n');
+    }
+    staff
+}
+
+/// Counts the letter names from middle C up to the pitch, so that C4 is 0, E4 is 2, and B3 is -1.
+fn staff_position(pitch: &Pitch) -> i32 {
+    ((pitch.1).0 as i32 - 4) * 7 + (pitch.0).0.steps_from_c() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn arpeggio() {
+        let arpeggio = parse_music("C4 E4 G4 C5").unwrap();
+        let expected = [
+            "------------",
+            "",
+            "------------",
+            "          o",
+            "------------",
+            "",
+            "-------o----",
+            "",
+            "----o-------",
+            "",
+            "-o-",
+        ];
+        assert_eq!(render_staff(&[&arpeggio]), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn voices_and_ledger_lines() {
+        let upper = parse_music("A5 F5").unwrap();
+        let lower = parse_music("A3 F#4 E4").unwrap();
+        let staff = render_staff(&[&upper, &lower]);
+        let rows: Vec<&str> = staff.lines().collect();
+        // From A5 down to A3, with a ledger line for A5 and two below the staff for A3
+        assert_eq!(rows.len(), 15);
+        assert_eq!(rows[0], "-o-");
+        assert_eq!(rows[2], "----o----");
+        assert_eq!(rows[9], "    o");
+        assert_eq!(rows[10], "-------o-");
+        assert_eq!(rows[12], "---");
+        assert_eq!(rows[14], "-o-");
+    }
+}
@@ -0,0 +1,97 @@
+//! Tuning: how large intervals are once they're sounded, for temperaments other than twelve-tone equal temperament.
+
+use crate::Interval;
+
+/// A way of tuning intervals, given by the frequency ratio of each interval.
+pub trait Tuning {
+    /// Gets the ratio of the top frequency to the bottom frequency of an interval, such as 2.0 for an octave.
+    fn ratio(&self, interval: Interval) -> f64;
+
+    /// Gets the size of an interval in cents, where an octave is 1200 cents.
+    fn cents(&self, interval: Interval) -> f64 {
+        1200.0 * self.ratio(interval).log2()
+    }
+
+    /// Gets the frequency an interval above `hz`. Comparing these across tunings gives the beat rate between them.
+    fn frequency_above(&self, hz: f64, interval: Interval) -> f64 {
+        hz * self.ratio(interval)
+    }
+}
+
+/// Twelve-tone equal temperament, where every semitone is 100 cents and enharmonic intervals are the same size.
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
+pub struct EqualTemperament;
+
+impl Tuning for EqualTemperament {
+    fn ratio(&self, interval: Interval) -> f64 {
+        2f64.powf(interval.semitones() as f64 / 12.0)
+    }
+
+    fn cents(&self, interval: Interval) -> f64 {
+        interval.cents()
+    }
+}
+
+/// Five-limit just intonation, where intervals are tuned to whole-number ratios so that a perfect fifth is 3:2 and a
+/// major third is 5:4.
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
+pub struct JustIntonation;
+
+/// Ratios for the major and perfect intervals from a unison to a seventh.
+const JUST_MAJOR_RATIOS: [f64; 7] = [1.0, 9.0 / 8.0, 5.0 / 4.0, 4.0 / 3.0, 3.0 / 2.0, 5.0 / 3.0, 15.0 / 8.0];
+
+/// The chromatic semitone, which raises a major interval to augmented or lowers it to minor.
+const JUST_CHROMATIC_SEMITONE: f64 = 25.0 / 24.0;
+
+impl Tuning for JustIntonation {
+    fn ratio(&self, interval: Interval) -> f64 {
+        let simple = interval.steps() % 7;
+        let octaves = interval.steps() / 7;
+        let deviation = interval.deviation();
+        // The usual ratios for the intervals that don't come out of a chromatic semitone from a major one
+        let ratio = match (simple, deviation) {
+            (1, -1) => 16.0 / 15.0,
+            (3, 1) => 45.0 / 32.0,
+            (4, -1) => 64.0 / 45.0,
+            _ => JUST_MAJOR_RATIOS[simple as usize] * JUST_CHROMATIC_SEMITONE.powi(deviation as i32),
+        };
+        ratio * 2f64.powi(octaves as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn cents() {
+        let close = |a: f64, b: f64| (a - b).abs() < 0.01;
+        // A perfect fifth is 700 cents when equal-tempered, and a little wider when just
+        assert!(close(Interval::PERFECT_FIFTH.cents(), 700.0));
+        assert!(close(EqualTemperament.cents(Interval::PERFECT_FIFTH), 700.0));
+        assert!(close(JustIntonation.cents(Interval::PERFECT_FIFTH), 701.96));
+        // A just major third is about 14 cents narrower than an equal-tempered one
+        assert!(close(JustIntonation.cents(Interval::MAJOR_THIRD), 386.31));
+        assert!(close(JustIntonation.ratio(Interval::MINOR_THIRD), 1.2));
+        assert!(close(JustIntonation.ratio(Interval::MINOR_SIXTH), 1.6));
+        assert!(close(JustIntonation.ratio(Interval::MINOR_SECOND), 16.0 / 15.0));
+        // Enharmonic intervals differ in just intonation
+        assert!(JustIntonation.cents(Interval::AUGMENTED_FOURTH) < JustIntonation.cents(Interval::DIMINISHED_FIFTH));
+        assert!(close(EqualTemperament.cents(Interval::AUGMENTED_FOURTH), EqualTemperament.cents(Interval::DIMINISHED_FIFTH)));
+        // Octaves are pure in both
+        assert!(close(JustIntonation.ratio(Interval::OCTAVE), 2.0));
+        assert!(close(EqualTemperament.ratio(Interval::OCTAVE), 2.0));
+        assert!(close(JustIntonation.ratio(Interval(IntervalQuality::Perfect, 12)), 3.0));
+    }
+
+    #[test]
+    fn beats() {
+        // A fifth above A3 is about 0.37 Hz flat of pure in equal temperament
+        let a3 = Pitch(Note(PitchBase::A, PitchModifier::Natural), Octave(3)).frequency_standard();
+        let tempered = EqualTemperament.frequency_above(a3, Interval::PERFECT_FIFTH);
+        let just = JustIntonation.frequency_above(a3, Interval::PERFECT_FIFTH);
+        assert!((just - 330.0).abs() < 0.01);
+        assert!((just - tempered - 0.37).abs() < 0.01);
+    }
+}
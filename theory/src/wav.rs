@@ -0,0 +1,147 @@
+//! Writing pitches out as WAV audio, synthesized as plain sine tones for a quick listen without a synthesizer.
+
+use crate::{Pitch, STANDARD_A4_HZ};
+use std::convert::TryFrom;
+use std::f64::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How long each note takes to fade in and out, in seconds, so that the tones don't click as they change.
+const FADE_SECONDS: f64 = 0.005;
+
+/// Renders the voices to a mono 16-bit WAV file, with each pitch played as a sine tone for `seconds_per_note` and the
+/// voices' notes at the same index sounding together. Fails if the sample rate is zero, the note length isn't a
+/// positive number of seconds, or the audio is too long for a WAV file to hold.
+pub fn render_wav<P: AsRef<Path>>(path: P, voices: &[&[Pitch]], sample_rate: u32, seconds_per_note: f64) -> io::Result<()> {
+    fs::write(path, to_wav(voices, sample_rate, seconds_per_note)?)
+}
+
+/// Encodes the voices as the contents of a WAV file, like `render_wav` but without writing them anywhere.
+pub fn to_wav(voices: &[&[Pitch]], sample_rate: u32, seconds_per_note: f64) -> io::Result<Vec<u8>> {
+    if sample_rate == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the sample rate must be at least 1 Hz"));
+    }
+    if !(seconds_per_note.is_finite() && seconds_per_note > 0.0) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "each note must last a positive number of seconds"));
+    }
+
+    // The sizes in the header are all u32s
+    let too_long = || io::Error::new(io::ErrorKind::InvalidInput, "the audio is too long to fit in a WAV file");
+    let byte_rate = sample_rate.checked_mul(2).ok_or_else(too_long)?;
+    let note_len = note_len(sample_rate, seconds_per_note);
+    let notes = voices.iter().map(|voice| voice.len()).max().unwrap_or(0);
+    let data_len = notes
+        .checked_mul(note_len)
+        .and_then(|samples| samples.checked_mul(2))
+        .and_then(|len| u32::try_from(len).ok())
+        .filter(|len| len.checked_add(36).is_some())
+        .ok_or_else(too_long)?;
+
+    let samples = mix(voices, sample_rate, note_len);
+
+    let mut data = b"RIFF".to_vec();
+    data.extend_from_slice(&(36 + data_len).to_le_bytes());
+    data.extend_from_slice(b"WAVE");
+
+    // Uncompressed PCM, one channel, two bytes per sample
+    data.extend_from_slice(b"fmt ");
+    data.extend_from_slice(&16u32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&sample_rate.to_le_bytes());
+    data.extend_from_slice(&byte_rate.to_le_bytes());
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(&16u16.to_le_bytes());
+
+    data.extend_from_slice(b"data");
+    data.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        data.extend_from_slice(&sample.to_le_bytes());
+    }
+    Ok(data)
+}
+
+/// Counts the samples in each note, which is never less than one.
+fn note_len(sample_rate: u32, seconds_per_note: f64) -> usize {
+    ((seconds_per_note * sample_rate as f64).round() as usize).max(1)
+}
+
+/// Adds up a sine tone, `note_len` samples long, for every note of every voice, scaled so that the voices together
+/// never clip.
+fn mix(voices: &[&[Pitch]], sample_rate: u32, note_len: usize) -> Vec<i16> {
+    let len = voices.iter().map(|voice| voice.len()).max().unwrap_or(0);
+    let fade_len = ((FADE_SECONDS * sample_rate as f64) as usize).min(note_len / 2).max(1);
+    let amplitude = i16::MAX as f64 / voices.len().max(1) as f64;
+
+    let mut mixed = vec![0.0; len * note_len];
+    for voice in voices {
+        for (idx, pitch) in voice.iter().enumerate() {
+            let hz = pitch.frequency(STANDARD_A4_HZ);
+            for sample in 0..note_len {
+                let envelope = (sample.min(note_len - 1 - sample) as f64 / fade_len as f64).min(1.0);
+                let t = sample as f64 / sample_rate as f64;
+                mixed[idx * note_len + sample] += amplitude * envelope * (2.0 * PI * hz * t).sin();
+            }
+        }
+    }
+    mixed.into_iter().map(|sample| sample.round() as i16).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn write_file() {
+        let cantus = parse_music("D4 F4 E4 D4").unwrap();
+        let counterpoint = parse_music("D3 D3 C3 D3").unwrap();
+        let path = std::env::temp_dir().join("theory_render_wav.wav");
+        render_wav(&path, &[&cantus, &counterpoint], 8000, 0.25).unwrap();
+
+        let data = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        // Four notes of 2000 samples each, at two bytes a sample
+        assert_eq!(&data[..4], b"RIFF");
+        assert_eq!(&data[4..8], &(36u32 + 16_000).to_le_bytes());
+        assert_eq!(&data[8..16], b"WAVEfmt ");
+        // PCM, mono, 8 kHz, 16 bits
+        assert_eq!(&data[20..24], &[1, 0, 1, 0]);
+        assert_eq!(&data[24..28], &8000u32.to_le_bytes());
+        assert_eq!(&data[34..36], &[16, 0]);
+        assert_eq!(&data[36..40], b"data");
+        assert_eq!(&data[40..44], &16_000u32.to_le_bytes());
+        assert_eq!(data.len(), 44 + 16_000);
+    }
+
+    #[test]
+    fn sine_tones() {
+        let a4 = parse_music("A4").unwrap();
+        let samples = mix(&[&a4], 44_100, 44_100);
+        assert_eq!(samples.len(), 44_100);
+        // The tone fades in from silence, and crosses zero going up 440 times a second
+        assert_eq!(samples[0], 0);
+        let crossings = samples.windows(2).filter(|pair| pair[0] < 0 && pair[1] >= 0).count();
+        assert!((439..=440).contains(&crossings), "{}", crossings);
+        assert!(samples.iter().any(|sample| *sample > i16::MAX / 2));
+
+        // Two voices share the full range between them, and the shorter voice falls silent
+        let samples = mix(&[&parse_music("A4 A4").unwrap(), &a4], 100, 100);
+        assert_eq!(samples.len(), 200);
+        assert!(samples[100..].iter().all(|sample| sample.unsigned_abs() <= i16::MAX as u16 / 2 + 1));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let cantus = parse_music("D4 F4 E4 D4").unwrap();
+        assert!(to_wav(&[&cantus], 0, 0.5).is_err());
+        assert!(to_wav(&[&cantus], 44_100, 0.0).is_err());
+        assert!(to_wav(&[&cantus], 44_100, f64::NAN).is_err());
+        assert_eq!(to_wav(&[], 44_100, 0.5).unwrap().len(), 44);
+        // Sizes that don't fit in the header are refused rather than wrapping
+        assert_eq!(to_wav(&[&cantus], u32::MAX, 0.5).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(to_wav(&[&cantus], 44_100, 1e9).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(to_wav(&[&cantus], 44_100, f64::MAX).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,106 @@
+//! Exercises the counterpoint solver through the crate's public API, the way the command-line tool uses it.
+
+#![cfg(feature = "std")]
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use theory::*;
+
+fn cantus() -> Vec<Pitch> {
+    parse_music("D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4").unwrap()
+}
+
+fn dorian() -> Scale {
+    Scale(Note::D, ScaleType::Dorian)
+}
+
+#[test]
+fn parses_and_solves() {
+    let cantus = cantus();
+    let rules = RuleSet::default();
+    let line = counterpoint(&cantus, &dorian(), Direction::Below, &rules, &mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(line.len(), cantus.len());
+    // The line closes on the final of the mode, below the cantus
+    assert!(line.iter().zip(&cantus).all(|(note, other)| note <= other));
+    assert_eq!(line[line.len() - 1].0, Note::D);
+
+    // The same seed finds the same line
+    let again = counterpoint(&cantus, &dorian(), Direction::Below, &rules, &mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(line, again);
+}
+
+#[test]
+fn searches() {
+    let cantus = cantus();
+    let scale = dorian();
+    let rules = RuleSet::default();
+
+    let all: Vec<Vec<Pitch>> = counterpoint_all(&cantus, &scale, Direction::Above, &rules).take(5).collect();
+    assert_eq!(all.len(), 5);
+    assert!(all.iter().enumerate().all(|(idx, line)| !all[idx + 1..].contains(line)));
+
+    assert_eq!(counterpoint_with_budget(&cantus, &scale, Direction::Above, &rules, 1, &mut StdRng::seed_from_u64(0)), Err(SearchError::OutOfBudget));
+    let best = counterpoint_best(&cantus, &scale, Direction::Above, &rules, 2_000, &mut StdRng::seed_from_u64(0)).unwrap();
+    assert_eq!(best.len(), cantus.len());
+
+    let (line, stats) = counterpoint_with_stats(&cantus, &scale, Direction::Above, &rules, &mut StdRng::seed_from_u64(0));
+    assert!(line.is_some());
+    assert!(stats.nodes >= cantus.len());
+    assert_eq!(stats.max_depth, cantus.len());
+}
+
+#[test]
+fn species_and_voices() {
+    let cantus = cantus();
+    let scale = dorian();
+    let rules = RuleSet::default();
+
+    let second = counterpoint_species(&cantus, &scale, Direction::Above, Species::Second, &rules, &mut StdRng::seed_from_u64(5)).unwrap();
+    assert_eq!(second.len(), 2 * cantus.len() - 1);
+
+    let voices = counterpoint_multi(&cantus, 2, &scale, &rules, &mut StdRng::seed_from_u64(5)).unwrap();
+    assert_eq!(voices.len(), 2);
+    assert!(voices.iter().all(|voice| voice.len() == cantus.len()));
+
+    let (direction, line) = counterpoint_either(&cantus, &scale, &rules, &mut StdRng::seed_from_u64(5)).unwrap();
+    let expected = if direction == Direction::Above { line[1] >= cantus[1] } else { line[1] <= cantus[1] };
+    assert!(expected);
+}
+
+#[test]
+fn unsolvable() {
+    // Without being able to move or repeat a note, there's no way past the opening
+    let cantus = cantus();
+    let stuck = RuleSet { max_leap: Interval::UNISON, max_consecutive_repeats: 1, ..RuleSet::default() };
+    assert_eq!(counterpoint(&cantus, &dorian(), Direction::Above, &stuck, &mut StdRng::seed_from_u64(0)), None);
+    let result = counterpoint_with_budget(&cantus, &dorian(), Direction::Above, &stuck, 1_000, &mut StdRng::seed_from_u64(0));
+    assert_eq!(result, Err(SearchError::NoSolution));
+    assert!(parse_music("D4 H4").is_err());
+}
+
+/// Generates counterpoint on the given side of the cantus for several seeds, and checks each line against the rules.
+fn assert_follows_rules(cantus: &[Pitch], scale: &Scale, direction: Direction) {
+    let rules = RuleSet::strict_fux();
+    for seed in 0..10 {
+        let line = counterpoint(cantus, scale, direction, &rules, &mut StdRng::seed_from_u64(seed)).unwrap();
+        assert_eq!(check_counterpoint(cantus, &line, scale, &rules), vec![], "seed {}: {:?}", seed, line);
+    }
+}
+
+#[test]
+fn fux_dorian_above() {
+    assert_follows_rules(&cantus(), &dorian(), Direction::Above);
+}
+
+#[test]
+fn fux_dorian_below() {
+    assert_follows_rules(&cantus(), &dorian(), Direction::Below);
+}
+
+#[test]
+fn fux_phrygian() {
+    let cantus = parse_music("E4 C4 D4 C4 A3 A4 G4 E4 F4 E4").unwrap();
+    let scale = Scale(Note::E, ScaleType::Phrygian);
+    assert_follows_rules(&cantus, &scale, Direction::Above);
+    assert_follows_rules(&cantus, &scale, Direction::Below);
+}